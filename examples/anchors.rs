@@ -0,0 +1,107 @@
+use eframe::egui;
+use egui::{vec2, Align2};
+use egui_cable::{prelude::*, response::PlugResponse};
+
+fn main() {
+    let native_options = eframe::NativeOptions {
+        default_theme: eframe::Theme::Light,
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        native_options,
+        Box::new(|_| Box::new(MyEguiApp::default())),
+    )
+    .expect("Failed to start native application");
+}
+
+/// A port drawn as a labeled box.
+#[derive(Debug)]
+struct LabeledPort(&'static str);
+
+impl egui::Widget for LabeledPort {
+    fn ui(self, ui: &mut egui::Ui) -> egui::Response {
+        let hovered = PortParams::get(ui).hovered;
+        let (rect, response) = ui.allocate_exact_size(vec2(80.0, 24.0), egui::Sense::click());
+        let visuals = if hovered {
+            ui.visuals().widgets.hovered
+        } else {
+            *ui.style().interact(&response)
+        };
+        ui.painter()
+            .rect(rect, 4.0, visuals.bg_fill, visuals.fg_stroke);
+        ui.painter().text(
+            rect.center(),
+            Align2::CENTER_CENTER,
+            self.0,
+            egui::FontId::default(),
+            visuals.text_color(),
+        );
+        response
+    }
+}
+
+fn apply(plug: &mut PlugResponse, to: &mut Option<usize>) {
+    if let Some(port_id) = plug.connected_to() {
+        *to = Some(*port_id.downcast_ref::<usize>().unwrap());
+    }
+    if plug.disconnected() {
+        *to = None;
+    }
+}
+
+struct MyEguiApp {
+    cables: Vec<(Option<usize>, Option<usize>)>,
+}
+
+impl Default for MyEguiApp {
+    fn default() -> Self {
+        MyEguiApp {
+            cables: vec![(Some(0), Some(3)), (Some(1), None)],
+        }
+    }
+}
+
+impl eframe::App for MyEguiApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.label(
+                "Plugs sit on the right edge of outputs, cables end at the left edge of inputs.",
+            );
+            CableCanvas::new("anchors").show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.vertical(|ui| {
+                        for (port, label) in [(0, "Out 1"), (1, "Out 2"), (2, "Out 3")] {
+                            let port = Port::new(port)
+                                .widget(LabeledPort(label))
+                                .anchor(Align2::RIGHT_CENTER);
+                            ui.add(port);
+                            ui.add_space(20.0);
+                        }
+                    });
+                    ui.add_space(200.0);
+                    ui.vertical(|ui| {
+                        for (port, label) in [(3, "In 1"), (4, "In 2"), (5, "In 3")] {
+                            let port = Port::new(port)
+                                .widget(LabeledPort(label))
+                                .line_anchor(Align2::LEFT_CENTER);
+                            ui.add(port);
+                            ui.add_space(20.0);
+                        }
+                    });
+                });
+                for (i, (in_plug, out_plug)) in self.cables.iter_mut().enumerate() {
+                    let plug =
+                        |to: &Option<usize>| to.map(Plug::to).unwrap_or_else(Plug::unplugged);
+                    let mut response = ui.add(Cable::new(i, plug(in_plug), plug(out_plug)));
+                    apply(&mut response.in_plug(), in_plug);
+                    apply(&mut response.out_plug(), out_plug);
+                }
+            });
+        });
+    }
+
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::WHITE.to_array()
+    }
+}
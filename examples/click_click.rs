@@ -0,0 +1,83 @@
+use eframe::egui;
+use egui_cable::{prelude::*, response::PlugResponse};
+
+fn main() {
+    let native_options = eframe::NativeOptions {
+        default_theme: eframe::Theme::Light,
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        native_options,
+        Box::new(|_| {
+            Box::new(MyEguiApp {
+                cables: vec![(Some(0), Some(1)), (Some(2), None)],
+            })
+        }),
+    )
+    .expect("Failed to start native application");
+}
+
+struct MyEguiApp {
+    // (in-plug, out-plug)
+    cables: Vec<(Option<usize>, Option<usize>)>,
+}
+
+fn plug(to: Option<usize>) -> Plug {
+    to.map(Plug::to).unwrap_or_else(Plug::unplugged)
+}
+
+fn apply(plug: &mut PlugResponse, to: &mut Option<usize>) {
+    if let Some(port_id) = plug.connected_to() {
+        *to = Some(*port_id.downcast_ref::<usize>().unwrap());
+    }
+    if plug.disconnected() {
+        *to = None;
+    }
+}
+
+impl eframe::App for MyEguiApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.label("Click a plug or a port, then click another port. Escape cancels.");
+            ui.label("Ctrl-click a cable to delete it, ctrl + right-drag to cut cables.");
+            ui.label("Double-click a cable control to reset its curve.");
+            let response = CableCanvas::new("click-click")
+                .connection_mode(ConnectionMode::ClickClick)
+                .cable_deletion(true)
+                .knife(true)
+                .relative_curves(true)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        for port in 0..4usize {
+                            ui.add(Port::new(port));
+                            ui.add_space(40.0);
+                        }
+                    });
+                    let mut deleted = None;
+                    for (i, (in_plug, out_plug)) in self.cables.iter_mut().enumerate() {
+                        let mut response = ui.add(Cable::new(i, plug(*in_plug), plug(*out_plug)));
+                        apply(&mut response.in_plug(), in_plug);
+                        apply(&mut response.out_plug(), out_plug);
+                        if response.delete_requested() {
+                            deleted = Some(i);
+                        }
+                    }
+                    if let Some(i) = deleted {
+                        self.cables.remove(i);
+                    }
+                });
+            if let Some(cut) = response.cables_cut() {
+                let mut i = 0;
+                self.cables.retain(|_| {
+                    i += 1;
+                    !cut.contains(&egui::Id::new(i - 1))
+                });
+            }
+        });
+    }
+
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::WHITE.to_array()
+    }
+}
@@ -2,8 +2,10 @@ use eframe::egui;
 use egui_cable::prelude::*;
 
 fn main() {
-    let mut native_options = eframe::NativeOptions::default();
-    native_options.default_theme = eframe::Theme::Light;
+    let native_options = eframe::NativeOptions {
+        default_theme: eframe::Theme::Light,
+        ..Default::default()
+    };
     eframe::run_native(
         "My egui App",
         native_options,
@@ -0,0 +1,115 @@
+use eframe::egui;
+use egui_cable::{prelude::*, response::PlugResponse};
+
+fn main() {
+    let native_options = eframe::NativeOptions {
+        default_theme: eframe::Theme::Light,
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        native_options,
+        Box::new(|_| {
+            Box::new(MyEguiApp {
+                cables: vec![
+                    CableModel::new(Some(0), Some(1)),
+                    CableModel::new(Some(0), Some(2)),
+                    CableModel::new(Some(3), None),
+                ],
+                plug_fan: PlugFan::Stack,
+            })
+        }),
+    )
+    .expect("Failed to start native application");
+}
+
+struct CableModel {
+    in_plug: Option<usize>,
+    out_plug: Option<usize>,
+    locked: bool,
+}
+
+impl CableModel {
+    fn new(in_plug: Option<usize>, out_plug: Option<usize>) -> Self {
+        CableModel {
+            in_plug,
+            out_plug,
+            locked: false,
+        }
+    }
+}
+
+struct MyEguiApp {
+    cables: Vec<CableModel>,
+    plug_fan: PlugFan,
+}
+
+fn plug(to: Option<usize>) -> Plug {
+    to.map(Plug::to).unwrap_or_else(Plug::unplugged)
+}
+
+fn apply(plug: &mut PlugResponse, to: &mut Option<usize>) {
+    if let Some(port_id) = plug.connected_to() {
+        *to = Some(*port_id.downcast_ref::<usize>().unwrap());
+    }
+    if plug.disconnected() {
+        *to = None;
+    }
+}
+
+impl eframe::App for MyEguiApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.label("Right-click a port, a plug or a cable control.");
+            ui.horizontal(|ui| {
+                ui.label("Plugs on the same port:");
+                ui.radio_value(&mut self.plug_fan, PlugFan::Stack, "Stack");
+                ui.radio_value(&mut self.plug_fan, PlugFan::Radial, "Radial");
+                ui.radio_value(&mut self.plug_fan, PlugFan::Row, "Row");
+            });
+            CableCanvas::new("context-menu")
+                .plug_fan(self.plug_fan)
+                .default_context_menus(true)
+                .cable_deletion(true)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        for port in 0..4usize {
+                            ui.add(Port::new(port).context_menu(move |ui, _| {
+                                ui.label(format!("Port {port}"));
+                            }));
+                            ui.add_space(40.0);
+                        }
+                    });
+                    let mut deleted = None;
+                    for (i, cable) in self.cables.iter_mut().enumerate() {
+                        let mut widget = Cable::new(i, plug(cable.in_plug), plug(cable.out_plug))
+                            .context_menu(move |ui, _| {
+                                ui.label(format!("Cable {i}"));
+                            });
+                        if cable.locked {
+                            widget = widget.lock();
+                        }
+                        let mut response = ui.add(widget);
+                        apply(&mut response.in_plug(), &mut cable.in_plug);
+                        apply(&mut response.out_plug(), &mut cable.out_plug);
+                        if response.swap_ends_requested() {
+                            std::mem::swap(&mut cable.in_plug, &mut cable.out_plug);
+                        }
+                        if let Some(lock) = response.lock_requested() {
+                            cable.locked = lock;
+                        }
+                        if response.delete_requested() {
+                            deleted = Some(i);
+                        }
+                    }
+                    if let Some(i) = deleted {
+                        self.cables.remove(i);
+                    }
+                });
+        });
+    }
+
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::WHITE.to_array()
+    }
+}
@@ -0,0 +1,110 @@
+use eframe::egui;
+use egui_cable::{
+    layout::{ForceLayout, LayeredLayout, LayoutGraph},
+    prelude::*,
+    response::PlugResponse,
+};
+
+fn main() {
+    let native_options = eframe::NativeOptions {
+        default_theme: eframe::Theme::Light,
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        native_options,
+        Box::new(|_| Box::new(MyEguiApp::default())),
+    )
+    .expect("Failed to start native application");
+}
+
+struct MyEguiApp {
+    cables: Vec<(Option<usize>, Option<usize>)>,
+    force: Option<ForceLayout>,
+}
+
+impl Default for MyEguiApp {
+    fn default() -> Self {
+        // the input of node i is port 2i and the output is port 2i + 1
+        let edges = [
+            (0, 1),
+            (0, 2),
+            (1, 3),
+            (2, 3),
+            (3, 4),
+            (1, 5),
+            (5, 4),
+            (4, 6),
+            (0, 6),
+        ];
+        MyEguiApp {
+            cables: edges
+                .into_iter()
+                .map(|(from, to)| (Some(from * 2 + 1), Some(to * 2)))
+                .collect(),
+            force: None,
+        }
+    }
+}
+
+fn apply(plug: &mut PlugResponse, to: &mut Option<usize>) {
+    if let Some(port_id) = plug.connected_to() {
+        *to = Some(*port_id.downcast_ref::<usize>().unwrap());
+    }
+    if plug.disconnected() {
+        *to = None;
+    }
+}
+
+impl eframe::App for MyEguiApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Arrange in layers").clicked() {
+                    let graph = LayoutGraph::from_canvas(ctx, "layout");
+                    LayeredLayout::default()
+                        .origin([20.0, 20.0])
+                        .run(&graph)
+                        .apply(ctx);
+                }
+                if ui.button("Untangle").clicked() {
+                    self.force = Some(ForceLayout::new(&LayoutGraph::from_canvas(ctx, "layout")));
+                }
+                if self.force.is_some() {
+                    ui.label("Drag nodes to pin them.");
+                }
+            });
+        });
+        // a step in each frame, before the nodes are shown
+        if let Some(force) = &mut self.force {
+            force.pin_dragged(ctx);
+            if force.step() {
+                force.apply(ctx);
+                ctx.request_repaint();
+            } else {
+                self.force = None;
+            }
+        }
+        egui::CentralPanel::default().show(ctx, |ui| {
+            CableCanvas::new("layout").pan_zoom(true).show(ui, |ui| {
+                for i in 0..7usize {
+                    Node::new(i, format!("Node {i}"))
+                        .input(Port::new(i * 2), "in")
+                        .output(Port::new(i * 2 + 1), "out")
+                        .show(ui.ctx(), |_| ());
+                }
+                for (i, (in_plug, out_plug)) in self.cables.iter_mut().enumerate() {
+                    let plug =
+                        |to: &Option<usize>| to.map(Plug::to).unwrap_or_else(Plug::unplugged);
+                    let mut response = ui.add(Cable::new(i, plug(in_plug), plug(out_plug)));
+                    apply(&mut response.in_plug(), in_plug);
+                    apply(&mut response.out_plug(), out_plug);
+                }
+            });
+        });
+    }
+
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::WHITE.to_array()
+    }
+}
@@ -0,0 +1,113 @@
+use eframe::egui;
+use egui::{pos2, vec2, Rect, Slider};
+use egui_cable::{
+    node::{align_nodes, distribute_nodes, Axis, NodeAlign},
+    prelude::*,
+    response::PlugResponse,
+    selection::selected_nodes,
+};
+
+fn main() {
+    let native_options = eframe::NativeOptions {
+        default_theme: eframe::Theme::Light,
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        native_options,
+        Box::new(|_| Box::new(MyEguiApp::default())),
+    )
+    .expect("Failed to start native application");
+}
+
+struct MyEguiApp {
+    cables: Vec<(Option<usize>, Option<usize>)>,
+    frequency: f32,
+    cutoff: f32,
+}
+
+impl Default for MyEguiApp {
+    fn default() -> Self {
+        MyEguiApp {
+            // outputs are 0, 1 and inputs are 10, 11
+            cables: vec![(Some(0), Some(10)), (Some(1), Some(11)), (None, None)],
+            frequency: 440.0,
+            cutoff: 1000.0,
+        }
+    }
+}
+
+fn apply(plug: &mut PlugResponse, to: &mut Option<usize>) {
+    if let Some(port_id) = plug.connected_to() {
+        *to = Some(*port_id.downcast_ref::<usize>().unwrap());
+    }
+    if plug.disconnected() {
+        *to = None;
+    }
+}
+
+impl eframe::App for MyEguiApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let selected = selected_nodes(ctx, "nodes");
+                if ui.button("Align tops").clicked() {
+                    align_nodes(ctx, &selected, NodeAlign::Top);
+                }
+                if ui.button("Align left edges").clicked() {
+                    align_nodes(ctx, &selected, NodeAlign::Left);
+                }
+                if ui.button("Distribute horizontally").clicked() {
+                    distribute_nodes(ctx, &selected, Axis::Horizontal);
+                }
+            });
+        });
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.label("Click or box-select nodes and drag them together, holding alt to move them off the grid. Middle-drag the background to pan.");
+            CableCanvas::new("nodes")
+                .pan_zoom(true)
+                .box_select(true)
+                .auto_pan(true)
+                .grid(SnapGrid::new(20.0))
+                .snap_radius(24.0)
+                .show(ui, |ui| {
+                    // shown before the nodes in it
+                    let sources = Rect::from_min_size(pos2(20.0, 50.0), vec2(600.0, 250.0));
+                    Group::new("sources", "Sources")
+                        .default_rect(sources)
+                        .show(ui, |ui| {
+                            Node::new("oscillator", "Oscillator")
+                                .default_pos([50.0, 100.0])
+                                .output(Port::new(0usize), "out")
+                                .show(ui.ctx(), |ui| {
+                                    let hz = Slider::new(&mut self.frequency, 20.0..=2000.0);
+                                    ui.add(hz.text("Hz"));
+                                });
+                            Node::new("filter", "Filter")
+                                .default_pos([350.0, 150.0])
+                                .input(Port::new(10usize), "in")
+                                .output(Port::new(1usize), "out")
+                                .show(ui.ctx(), |ui| {
+                                    let cutoff = Slider::new(&mut self.cutoff, 20.0..=20000.0);
+                                    ui.add(cutoff.text("cutoff"));
+                                });
+                        });
+                    Node::new("speaker", "Speaker")
+                        .default_pos([650.0, 100.0])
+                        .input(Port::new(11usize), "in")
+                        .show(ui.ctx(), |ui| ui.label("🔊"));
+                    for (i, (in_plug, out_plug)) in self.cables.iter_mut().enumerate() {
+                        let plug =
+                            |to: &Option<usize>| to.map(Plug::to).unwrap_or_else(Plug::unplugged);
+                        let mut response = ui.add(Cable::new(i, plug(in_plug), plug(out_plug)));
+                        apply(&mut response.in_plug(), in_plug);
+                        apply(&mut response.out_plug(), out_plug);
+                    }
+                });
+        });
+    }
+
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::WHITE.to_array()
+    }
+}
@@ -0,0 +1,80 @@
+use eframe::egui;
+use egui::{emath::TSTransform, pos2, vec2, Rect};
+use egui_cable::{canvas::set_canvas_transform, prelude::*, response::PlugResponse};
+
+fn main() {
+    let native_options = eframe::NativeOptions {
+        default_theme: eframe::Theme::Light,
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        native_options,
+        Box::new(|_| Box::new(MyEguiApp::default())),
+    )
+    .expect("Failed to start native application");
+}
+
+struct MyEguiApp {
+    cables: Vec<(Option<usize>, Option<usize>)>,
+}
+
+impl Default for MyEguiApp {
+    fn default() -> Self {
+        MyEguiApp {
+            cables: (0..5).map(|i| (Some(i), Some(9 - i))).collect(),
+        }
+    }
+}
+
+fn apply(plug: &mut PlugResponse, to: &mut Option<usize>) {
+    if let Some(port_id) = plug.connected_to() {
+        *to = Some(*port_id.downcast_ref::<usize>().unwrap());
+    }
+    if plug.disconnected() {
+        *to = None;
+    }
+}
+
+impl eframe::App for MyEguiApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Drag the background or scroll to pan, ctrl-scroll or pinch to zoom.");
+                if ui.button("Reset view").clicked() {
+                    set_canvas_transform(ctx, "pan-zoom", TSTransform::IDENTITY);
+                }
+            });
+        });
+        egui::Window::new("Minimap")
+            .anchor(egui::Align2::RIGHT_BOTTOM, vec2(-10.0, -10.0))
+            .resizable(false)
+            .show(ctx, |ui| ui.add(Minimap::new("pan-zoom")));
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let response = CableCanvas::new("pan-zoom")
+                .pan_zoom(true)
+                .auto_pan(true)
+                .show(ui, |ui| {
+                    for port in 0..10usize {
+                        let pos = pos2(
+                            100.0 + (port / 5) as f32 * 400.0,
+                            100.0 + (port % 5) as f32 * 80.0,
+                        );
+                        ui.put(Rect::from_min_size(pos, vec2(16.0, 16.0)), Port::new(port));
+                    }
+                    for (i, (in_plug, out_plug)) in self.cables.iter_mut().enumerate() {
+                        let plug =
+                            |to: &Option<usize>| to.map(Plug::to).unwrap_or_else(Plug::unplugged);
+                        let mut response = ui.add(Cable::new(i, plug(in_plug), plug(out_plug)));
+                        apply(&mut response.in_plug(), in_plug);
+                        apply(&mut response.out_plug(), out_plug);
+                    }
+                });
+            ui.label(format!("Zoom: {:.0}%", response.transform.scaling * 100.0));
+        });
+    }
+
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::WHITE.to_array()
+    }
+}
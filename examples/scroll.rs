@@ -0,0 +1,77 @@
+use eframe::egui;
+use egui_cable::{prelude::*, response::PlugResponse};
+
+fn main() {
+    let native_options = eframe::NativeOptions {
+        default_theme: eframe::Theme::Light,
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        native_options,
+        Box::new(|_| Box::new(MyEguiApp::default())),
+    )
+    .expect("Failed to start native application");
+}
+
+struct MyEguiApp {
+    cables: Vec<(Option<usize>, Option<usize>)>,
+}
+
+impl Default for MyEguiApp {
+    fn default() -> Self {
+        MyEguiApp {
+            cables: (0..10).map(|i| (Some(i), Some(100 + i * 2))).collect(),
+        }
+    }
+}
+
+fn apply(plug: &mut PlugResponse, to: &mut Option<usize>) {
+    if let Some(port_id) = plug.connected_to() {
+        *to = Some(*port_id.downcast_ref::<usize>().unwrap());
+    }
+    if plug.disconnected() {
+        *to = None;
+    }
+}
+
+impl eframe::App for MyEguiApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.label("Scroll the lists, or drag a plug to their edge. Plugs on hidden ports wait at the edge.");
+            CableCanvas::new("scroll")
+                .clip_cables(CableClip::Canvas)
+                .auto_pan(true)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        for (list, ports) in [("left", 0..20), ("right", 100..120)] {
+                            egui::ScrollArea::vertical()
+                                .id_source(list)
+                                .max_height(200.0)
+                                .show(ui, |ui| {
+                                    for port in ports {
+                                        ui.horizontal(|ui| {
+                                            ui.add(Port::new(port));
+                                            ui.label(format!("Port {port}"));
+                                        });
+                                        ui.add_space(10.0);
+                                    }
+                                });
+                            ui.add_space(200.0);
+                        }
+                    });
+                    for (i, (in_plug, out_plug)) in self.cables.iter_mut().enumerate() {
+                        let plug =
+                            |to: &Option<usize>| to.map(Plug::to).unwrap_or_else(Plug::unplugged);
+                        let mut response = ui.add(Cable::new(i, plug(in_plug), plug(out_plug)));
+                        apply(&mut response.in_plug(), in_plug);
+                        apply(&mut response.out_plug(), out_plug);
+                    }
+                });
+        });
+    }
+
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::WHITE.to_array()
+    }
+}
@@ -0,0 +1,110 @@
+use eframe::egui;
+use egui::Color32;
+use egui_cable::{prelude::*, selection::selected_cables};
+
+fn main() {
+    let native_options = eframe::NativeOptions {
+        default_theme: eframe::Theme::Light,
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        native_options,
+        Box::new(|_| {
+            Box::new(MyEguiApp {
+                cables: (0..4)
+                    .map(|i| CableModel {
+                        id: i,
+                        ports: (i * 2, i * 2 + 1),
+                        color: None,
+                        locked: false,
+                    })
+                    .collect(),
+            })
+        }),
+    )
+    .expect("Failed to start native application");
+}
+
+struct CableModel {
+    id: usize,
+    ports: (usize, usize),
+    color: Option<Color32>,
+    locked: bool,
+}
+
+struct MyEguiApp {
+    cables: Vec<CableModel>,
+}
+
+impl eframe::App for MyEguiApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let selected = selected_cables(ctx, "selection");
+        let is_selected = |cable: &CableModel| selected.contains(&egui::Id::new(cable.id));
+
+        egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!("{} selected", selected.len()));
+                for (name, color) in [("Red", Color32::RED), ("Blue", Color32::BLUE)] {
+                    if ui.button(name).clicked() {
+                        for cable in self.cables.iter_mut().filter(|c| is_selected(c)) {
+                            cable.color = Some(color);
+                        }
+                    }
+                }
+                if ui.button("Delete").clicked() {
+                    self.cables.retain(|c| !is_selected(c));
+                }
+            });
+        });
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.label("Click, shift-click or drag a box on the background to select cables.");
+            let response = CableCanvas::new("selection")
+                .box_select(true)
+                .cable_deletion(true)
+                .default_context_menus(true)
+                .show(ui, |ui| {
+                    for row in 0..4usize {
+                        ui.horizontal(|ui| {
+                            ui.add(Port::new(row * 2));
+                            ui.add_space(200.0);
+                            ui.add(Port::new(row * 2 + 1));
+                        });
+                        ui.add_space(40.0);
+                    }
+                    let mut deleted = vec![];
+                    for cable in self.cables.iter_mut() {
+                        let mut widget =
+                            Cable::new(cable.id, Plug::to(cable.ports.0), Plug::to(cable.ports.1));
+                        if let Some(color) = cable.color {
+                            widget = widget.color(color);
+                        }
+                        if cable.locked {
+                            widget = widget.lock();
+                        }
+                        let mut response = ui.add(widget);
+                        if response.delete_requested() {
+                            deleted.push(cable.id);
+                        }
+                        if let Some(lock) = response.lock_requested() {
+                            cable.locked = lock;
+                        }
+                    }
+                    self.cables.retain(|c| !deleted.contains(&c.id));
+                });
+            // "Lock" or "Unlock" on a selected cable applies to the whole selection
+            for (cable_id, lock) in response.locks_requested() {
+                for cable in self.cables.iter_mut() {
+                    if egui::Id::new(cable.id) == cable_id {
+                        cable.locked = lock;
+                    }
+                }
+            }
+        });
+    }
+
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::WHITE.to_array()
+    }
+}
@@ -0,0 +1,92 @@
+use eframe::egui;
+use egui_cable::{prelude::*, response::PlugResponse};
+
+fn main() {
+    let native_options = eframe::NativeOptions {
+        default_theme: eframe::Theme::Light,
+        ..Default::default()
+    };
+    eframe::run_native(
+        "My egui App",
+        native_options,
+        Box::new(|_| Box::new(MyEguiApp::default())),
+    )
+    .expect("Failed to start native application");
+}
+
+struct MyEguiApp {
+    cables: Vec<(Option<usize>, Option<usize>)>,
+    layering: CableLayering,
+    open: [bool; 2],
+}
+
+impl Default for MyEguiApp {
+    fn default() -> Self {
+        MyEguiApp {
+            cables: vec![(Some(0), Some(2)), (Some(1), Some(4)), (Some(3), Some(5))],
+            layering: CableLayering::Ports,
+            open: [true; 2],
+        }
+    }
+}
+
+fn apply(plug: &mut PlugResponse, to: &mut Option<usize>) {
+    if let Some(port_id) = plug.connected_to() {
+        *to = Some(*port_id.downcast_ref::<usize>().unwrap());
+    }
+    if plug.disconnected() {
+        *to = None;
+    }
+}
+
+impl eframe::App for MyEguiApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Cables are painted");
+                ui.radio_value(&mut self.layering, CableLayering::Ports, "with their ports");
+                ui.radio_value(
+                    &mut self.layering,
+                    CableLayering::Foreground,
+                    "above everything",
+                );
+            });
+            CableCanvas::new("windows")
+                .layering(self.layering)
+                .show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.add(Port::new(0));
+                        ui.add(Port::new(1));
+                    });
+                    let windows = [("Window A", 2..4), ("Window B", 4..6)];
+                    for ((window, ports), open) in windows.into_iter().zip(&mut self.open) {
+                        // cables to a closed window end at its checkbox
+                        PortScope::new(window).show(ui, |ui| {
+                            let checkbox = ui.checkbox(open, window);
+                            egui::Window::new(window).open(open).show(ctx, |ui| {
+                                ui.label("Drag this window over the others.");
+                                ui.horizontal(|ui| {
+                                    for port in ports {
+                                        ui.add(Port::new(port));
+                                        ui.add_space(40.0);
+                                    }
+                                });
+                            });
+                            checkbox
+                        });
+                    }
+                    for (i, (in_plug, out_plug)) in self.cables.iter_mut().enumerate() {
+                        let plug =
+                            |to: &Option<usize>| to.map(Plug::to).unwrap_or_else(Plug::unplugged);
+                        let mut response = ui.add(Cable::new(i, plug(in_plug), plug(out_plug)));
+                        apply(&mut response.in_plug(), in_plug);
+                        apply(&mut response.out_plug(), out_plug);
+                    }
+                });
+        });
+    }
+
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::WHITE.to_array()
+    }
+}
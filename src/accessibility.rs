@@ -0,0 +1,131 @@
+//! Screen reader support.
+//!
+//! Widget infos are always published, and AccessKit nodes get relationships with the `accesskit` feature.
+
+use egui::{Response, WidgetInfo, WidgetType};
+
+use crate::{cable::CableEnds, port::PortId, state::State};
+
+/// Describes a port by its label and the ports connected to it.
+pub(crate) fn describe_port(state: &State, response: &Response, port_id: &PortId, label: &str) {
+    response.widget_info(|| WidgetInfo::labeled(WidgetType::Button, label));
+
+    let mut peers: Vec<PortId> = state
+        .all_cable_ends()
+        .into_iter()
+        .filter_map(|ends| match (ends.in_port, ends.out_port) {
+            (Some(in_port), Some(out_port)) if &in_port == port_id => Some(out_port),
+            (Some(in_port), Some(out_port)) if &out_port == port_id => Some(in_port),
+            _ => None,
+        })
+        .collect();
+    peers.sort_by_key(|peer| port_label(state, peer));
+    let peer_labels: Vec<String> = peers.iter().map(|peer| port_label(state, peer)).collect();
+    let description = if peer_labels.is_empty() {
+        "Not connected".to_string()
+    } else {
+        format!("Connected to {}", peer_labels.join(", "))
+    };
+
+    #[cfg(feature = "accesskit")]
+    response.ctx.accesskit_node_builder(response.id, |builder| {
+        builder.set_description(description);
+        for peer in &peers {
+            if let Some(widget_id) = state.port_widget_id(peer) {
+                builder.push_detail(widget_id.value().into());
+            }
+        }
+    });
+    #[cfg(not(feature = "accesskit"))]
+    let _ = description;
+}
+
+/// Describes a cable by the ports of its ends.
+pub(crate) fn describe_cable(state: &State, response: &Response, ends: &CableEnds) {
+    let end_label = |port_id: &Option<PortId>| {
+        port_id
+            .as_ref()
+            .map(|port_id| port_label(state, port_id))
+            .unwrap_or_else(|| "nothing".to_string())
+    };
+    let label = format!(
+        "Cable from {} to {}",
+        end_label(&ends.in_port),
+        end_label(&ends.out_port)
+    );
+    response.widget_info(|| WidgetInfo::labeled(WidgetType::Other, &label));
+
+    #[cfg(feature = "accesskit")]
+    response.ctx.accesskit_node_builder(response.id, |builder| {
+        for port_id in ends.in_port.iter().chain(&ends.out_port) {
+            if let Some(widget_id) = state.port_widget_id(port_id) {
+                builder.push_detail(widget_id.value().into());
+            }
+        }
+    });
+}
+
+/// Announces a connection change to screen readers.
+pub(crate) fn announce(ctx: &egui::Context, message: String) {
+    #[cfg(feature = "accesskit")]
+    ctx.accesskit_node_builder(egui::Id::new("egui_cable_announcement"), |builder| {
+        use egui::accesskit::{Live, Role};
+        builder.set_role(Role::Status);
+        builder.set_live(Live::Polite);
+        builder.set_name(message.clone());
+    });
+    ctx.output_mut(|output| {
+        output.events.push(egui::output::OutputEvent::ValueChanged(
+            WidgetInfo::labeled(WidgetType::Other, message),
+        ))
+    });
+}
+
+pub(crate) fn port_label(state: &State, port_id: &PortId) -> String {
+    state.port_label(port_id).unwrap_or_else(|| port_id.label())
+}
+
+#[cfg(all(test, feature = "accesskit"))]
+mod tests {
+    use egui::accesskit::Node;
+
+    use crate::prelude::*;
+
+    fn nodes(ctx: &egui::Context) -> Vec<Node> {
+        let output = ctx.run(Default::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.add(Port::new(0).label("Input"));
+                ui.add(Port::new(1).label("Output"));
+                ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+            });
+        });
+        output
+            .platform_output
+            .accesskit_update
+            .unwrap()
+            .nodes
+            .into_iter()
+            .map(|(_, node)| node)
+            .collect()
+    }
+
+    #[test]
+    fn ports_and_cables() {
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        nodes(&ctx);
+        let nodes = nodes(&ctx);
+        let node = |name: &str| {
+            nodes
+                .iter()
+                .find(|node| node.name() == Some(name))
+                .unwrap_or_else(|| panic!("no node named {name}"))
+        };
+
+        let input = node("Input");
+        assert_eq!(input.description(), Some("Connected to Output"));
+        assert_eq!(input.details().len(), 1);
+        assert_eq!(node("Output").description(), Some("Connected to Input"));
+        assert_eq!(node("Cable from Input to Output").details().len(), 2);
+    }
+}
@@ -0,0 +1,206 @@
+use egui::{emath::TSTransform, vec2, Id, Pos2, Rect, Ui, Vec2};
+
+use crate::{canvas::CanvasSettings, plug::DraggedPlug, state::State};
+
+// distance from the edge of the view where panning starts
+const MARGIN: f32 = 40.0;
+// points per second at the edge
+const MAX_SPEED: f32 = 600.0;
+
+/// Returns how far the contents move in this frame to bring the view toward a plug near its edge.
+/// The speed grows as the plug gets closer to the edge, and is at its maximum past it.
+pub(crate) fn edge_delta(view: Rect, pos: Pos2, dt: f32) -> Vec2 {
+    if !view.expand(MARGIN).contains(pos) {
+        return Vec2::ZERO;
+    }
+    let speed = |inside: f32| MAX_SPEED * (1.0 - inside / MARGIN).clamp(0.0, 1.0);
+    let delta = vec2(
+        speed(pos.x - view.left()) - speed(view.right() - pos.x),
+        speed(pos.y - view.top()) - speed(view.bottom() - pos.y),
+    );
+    delta * dt
+}
+
+/// Returns the delta for the first plug of the canvas near the edge of the view, if any.
+fn plugs_delta(
+    plugs: Vec<DraggedPlug>,
+    canvas: Option<Id>,
+    dt: f32,
+    view: Rect,
+    to_view: impl Fn(Pos2) -> Pos2,
+) -> Option<Vec2> {
+    plugs
+        .into_iter()
+        .filter(|plug| plug.auto_pan && plug.canvas == canvas)
+        .map(|plug| edge_delta(view, to_view(plug.pos), dt))
+        .find(|delta| *delta != Vec2::ZERO)
+}
+
+/// Scrolls the scroll area around the ui toward a plug dragged near its edge.
+pub(crate) fn scroll_toward_plugs(ui: &Ui, state: &State) {
+    let settings = CanvasSettings::current(ui.ctx());
+    let clip = ui.clip_rect();
+    // Outside of any scroll area, the scroll would go to the next scroll area shown in the frame instead.
+    // A panned canvas pans its contents by itself.
+    if !settings.auto_pan || settings.contents_clip == Some(clip) {
+        return;
+    }
+    let dt = ui.input(|input| input.stable_dt);
+    if let Some(delta) = plugs_delta(state.dragged_plugs(), settings.id, dt, clip, |pos| pos) {
+        ui.scroll_with_delta(delta);
+        ui.ctx().request_repaint();
+    }
+}
+
+/// Pans the contents of a canvas toward a plug dragged near the edge of the canvas rect.
+pub(crate) fn pan_toward_plugs(
+    ctx: &egui::Context,
+    canvas: Id,
+    rect: Rect,
+    transform: TSTransform,
+) -> TSTransform {
+    let state = State::get_with_ctx(ctx);
+    let dt = ctx.input(|input| input.stable_dt);
+    let plugs = state.dragged_plugs();
+    match plugs_delta(plugs, Some(canvas), dt, rect, |pos| transform * pos) {
+        Some(delta) => {
+            ctx.request_repaint();
+            TSTransform::from_translation(delta) * transform
+        }
+        None => transform,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, Event, Modifiers, TouchPhase};
+
+    use super::*;
+    use crate::{canvas::Viewport, prelude::*, test_utils::*};
+
+    /// Renders ten ports in a scroll area, or on a panned canvas, with a cable plugged into the first one,
+    /// and returns the rects of the first port and the unplugged plug.
+    fn run(ctx: &egui::Context, pan_zoom: bool, events: Vec<Event>) -> (Rect, Rect) {
+        let canvas = CableCanvas::new("canvas").auto_pan(true).pan_zoom(pan_zoom);
+        run_canvas(ctx, canvas, Modifiers::NONE, events, |ui| {
+            let mut port = Rect::NOTHING;
+            let mut ports = |ui: &mut Ui| {
+                for i in 0..10 {
+                    let rect = ui.add(Port::new(i)).rect;
+                    if i == 0 {
+                        port = rect;
+                    }
+                    ui.add_space(50.0);
+                }
+            };
+            if pan_zoom {
+                ports(ui);
+            } else {
+                egui::ScrollArea::vertical()
+                    .max_height(200.0)
+                    .show(ui, ports);
+            }
+            let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::unplugged()));
+            (port, response.out_plug().rect)
+        })
+        .inner
+    }
+
+    /// Drags the unplugged plug to the position and holds it there for a while.
+    fn drag_and_hold(ctx: &egui::Context, pan_zoom: bool, to: Pos2) {
+        let (_, plug) = run(ctx, pan_zoom, vec![]);
+        let from = plug.center();
+        run(
+            ctx,
+            pan_zoom,
+            vec![Event::PointerMoved(from), button(from, true)],
+        );
+        for _ in 0..20 {
+            run(ctx, pan_zoom, vec![Event::PointerMoved(to)]);
+        }
+    }
+
+    #[test]
+    fn scroll_toward_dragged_plug() {
+        let ctx = egui::Context::default();
+        let (port, _) = run(&ctx, false, vec![]);
+        // near the bottom of the scroll area
+        drag_and_hold(&ctx, false, pos2(port.center().x, port.top() + 190.0));
+        let (scrolled, _) = run(&ctx, false, vec![]);
+        assert!(scrolled.top() < port.top());
+    }
+
+    #[test]
+    fn pan_toward_dragged_plug() {
+        let ctx = egui::Context::default();
+        run(&ctx, true, vec![]);
+        let rect = Viewport::get(&ctx, Id::new("canvas")).unwrap().rect;
+        // near the right edge of the canvas
+        drag_and_hold(&ctx, true, pos2(rect.right() - 10.0, rect.center().y));
+        let transform = ctx.data(|data| {
+            data.get_temp::<TSTransform>(Id::new("canvas").with("transform"))
+                .unwrap()
+        });
+        assert!(transform.translation.x < 0.0);
+        assert_eq!(transform.translation.y, 0.0);
+    }
+
+    /// Renders a port outside of any scroll area with a cable plugged into it, then a scroll area
+    /// at the bottom of a small screen, and returns the rect of the unplugged plug and the offset of the scroll area.
+    fn run_with_scroll_area_after(ctx: &egui::Context, events: Vec<Event>) -> (Rect, Vec2) {
+        let input = egui::RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, vec2(400.0, 400.0))),
+            events,
+            ..Default::default()
+        };
+        let mut output = None;
+        let _ = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let canvas = CableCanvas::new("canvas").auto_pan(true);
+                let plug = canvas
+                    .show(ui, |ui| {
+                        ui.add(Port::new(0));
+                        let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::unplugged()));
+                        response.out_plug().rect
+                    })
+                    .inner;
+                ui.add_space(250.0);
+                let offset = egui::ScrollArea::vertical()
+                    .auto_shrink(false)
+                    .show(ui, |ui| {
+                        for i in 0..20 {
+                            ui.label(format!("Row {i}"));
+                        }
+                    })
+                    .state
+                    .offset;
+                output = Some((plug, offset));
+            });
+        });
+        output.unwrap()
+    }
+
+    #[test]
+    fn no_scroll_outside_of_scroll_areas() {
+        let ctx = egui::Context::default();
+        run_with_scroll_area_after(&ctx, vec![]);
+        let (plug, _) = run_with_scroll_area_after(&ctx, vec![]);
+        // a touch drags the plug near the bottom of the screen while the mouse hovers the scroll area
+        let hover = Event::PointerMoved(pos2(200.0, 350.0));
+        let touch = |phase, pos| Event::Touch {
+            device_id: egui::TouchDeviceId(1),
+            id: egui::TouchId(1),
+            phase,
+            pos,
+            force: None,
+        };
+        let from = plug.center();
+        run_with_scroll_area_after(&ctx, vec![hover.clone(), touch(TouchPhase::Start, from)]);
+        let to = pos2(from.x, 390.0);
+        for _ in 0..20 {
+            run_with_scroll_area_after(&ctx, vec![hover.clone(), touch(TouchPhase::Move, to)]);
+        }
+        let (_, offset) = run_with_scroll_area_after(&ctx, vec![hover]);
+        assert_eq!(offset, Vec2::ZERO);
+    }
+}
@@ -1,16 +1,26 @@
 use std::fmt::Debug;
 use std::hash::Hash;
 
-use egui::{pos2, vec2, Id, Order, Pos2, Vec2, Widget};
+use egui::{pos2, vec2, Id, LayerId, Pos2, Rect, Vec2, Widget};
 use epaint::{Color32, QuadraticBezierShape};
 
 use crate::{
+    accessibility::describe_cable,
     cable_control::CableControl,
+    canvas::{CableLayering, CanvasSettings},
+    context_menu::{self, ContextMenu},
     custom_widget::CustomWidget,
     default_cable::{DefaultCable, DefaultControl},
+    grid::SnapGrid,
+    path::{route_around, CablePath, PathShape},
     plug::{PlugId, PlugType},
     prelude::*,
+    selection::{
+        add_to_selection, deselected_by_click, extends_selection, is_cable_selected_on,
+        remove_from_selection, select_cables_on,
+    },
     state::State,
+    utils::{show_on_layer, upper_layer},
 };
 
 pub type CableId = Id;
@@ -22,6 +32,9 @@ pub struct Cable {
     out_plug: Plug,
     widget: Option<CustomWidget>,
     control_widget: Option<CustomWidget>,
+    color: Option<Color32>,
+    locked: bool,
+    context_menu: Option<ContextMenu>,
 }
 
 impl Cable {
@@ -36,9 +49,34 @@ impl Cable {
             out_plug,
             widget: None,
             control_widget: None,
+            color: None,
+            locked: false,
+            context_menu: None,
         }
     }
 
+    /// Overrides the color of the cable line.
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Adds entries to the context menu of the cable control, above the built-in ones.
+    pub fn context_menu(
+        mut self,
+        add_contents: impl FnOnce(&mut egui::Ui, CableId) + 'static,
+    ) -> Self {
+        let id = self.id;
+        self.context_menu = Some(ContextMenu::new(move |ui| add_contents(ui, id)));
+        self
+    }
+
+    /// Locks both plugs and the cable control. A locked cable can still be selected.
+    pub fn lock(mut self) -> Self {
+        self.locked = true;
+        self
+    }
+
     pub fn widget(mut self, widget: impl Into<CustomWidget>) -> Self {
         self.widget = Some(widget.into());
         self
@@ -53,19 +91,34 @@ impl Cable {
 #[derive(Clone, Debug)]
 pub(crate) struct CableState {
     pub bezier_control_point_offset: Vec2,
+    // the offset in the basis of the cable direction and its normal, used with relative curves
+    pub relative_offset: Option<Vec2>,
     pub dragged: bool,
     pub drag_offset: Vec2,
     pub active: bool,
+    pub focused: bool,
+    pub context_menu_open: bool,
     pub in_vec: Option<Vec2>,
     pub out_vec: Option<Vec2>,
 }
 
+/// Ports the plugs of a cable are plugged into.
+#[derive(Clone, Debug)]
+pub(crate) struct CableEnds {
+    pub cable_id: CableId,
+    pub in_port: Option<PortId>,
+    pub out_port: Option<PortId>,
+}
+
 impl Default for CableState {
     fn default() -> Self {
         Self {
             // Default is not zero to make sophisticated cable view.
             bezier_control_point_offset: vec2(20.0, 25.0),
+            relative_offset: None,
             active: false,
+            focused: false,
+            context_menu_open: false,
             dragged: false,
             drag_offset: vec2(0.0, 0.0),
             in_vec: None,
@@ -74,45 +127,138 @@ impl Default for CableState {
     }
 }
 
+impl CableState {
+    /// Goes back to the default shape.
+    pub fn reset_curve(&mut self) {
+        let default = CableState::default();
+        self.bezier_control_point_offset = default.bezier_control_point_offset;
+        self.relative_offset = default.relative_offset;
+    }
+}
+
+/// Resets the shape of the cable changed by dragging its control.
+pub fn reset_curve(ctx: &egui::Context, cable_id: CableId) {
+    State::update_with_ctx(ctx, |state| {
+        if let Some(mut cable_state) = state.cable_state(&cable_id) {
+            cable_state.reset_curve();
+            state.update_cable_state(cable_id, cable_state);
+        }
+    });
+}
+
+/// Expresses an offset as a combination of the vector between the ends and its normal.
+/// Returns `None` if the ends are too close to make a basis.
+fn to_relative(offset: Vec2, in_pos: Pos2, out_pos: Pos2) -> Option<Vec2> {
+    let direction = out_pos - in_pos;
+    let length_sq = direction.length_sq();
+    (length_sq >= 1.0).then(|| {
+        vec2(
+            offset.dot(direction) / length_sq,
+            offset.dot(direction.rot90()) / length_sq,
+        )
+    })
+}
+
+fn from_relative(relative: Vec2, in_pos: Pos2, out_pos: Pos2) -> Option<Vec2> {
+    let direction = out_pos - in_pos;
+    (direction.length_sq() >= 1.0).then(|| direction * relative.x + direction.rot90() * relative.y)
+}
+
 impl Widget for Cable {
     fn ui(self, ui: &mut egui::Ui) -> egui::Response {
         // This must be out of area to get the next widget pos.
         let next_widget_position = ui.next_widget_position();
-        egui::Area::new(self.id)
-            .order(Order::Foreground)
-            // This should be the top-left of the window
-            .current_pos(pos2(0.0, 0.0))
+        let ends = CableEnds {
+            cable_id: self.id,
+            in_port: self.in_plug.plug_to.clone(),
+            out_port: self.out_plug.plug_to.clone(),
+        };
+        let layer = cable_layer(ui.ctx(), &ends);
+        // This should be the top-left of the window
+        show_on_layer(
+            ui.ctx(),
+            self.id,
+            layer,
+            pos2(0.0, 0.0),
             // This is important to make other widgets intractive even when behind a cable
-            .interactable(false)
-            .show(ui.ctx(), |ui| {
+            |area| area.interactable(false),
+            |ui| {
                 let mut cable_state = State::get(ui).cable_state(&self.id).unwrap_or_default();
+                let settings = CanvasSettings::current(ui.ctx());
+                let canvas = settings.id.unwrap_or(Id::NULL);
+                cable_state.active = is_cable_selected_on(ui.ctx(), canvas, self.id);
+                if let Some(clip_rect) = settings.clip_rect {
+                    ui.set_clip_rect(clip_rect.intersect(ui.clip_rect()));
+                }
+
+                let (mut in_plug, mut out_plug) = (self.in_plug, self.out_plug);
+                if self.locked {
+                    in_plug = in_plug.lock();
+                    out_plug = out_plug.lock();
+                }
 
                 // fixme? This could be more smart.
                 let default_in_pos = next_widget_position + vec2(10.0, 0.0);
                 let default_out_pos = next_widget_position + vec2(50.0, 0.0);
 
                 let in_response = ui.add(
-                    self.in_plug
+                    in_plug
                         .id(PlugId::new(self.id, PlugType::In))
                         .default_pos_no_overwrite(default_in_pos)
                         .cable_active(cable_state.active)
                         .vec(cable_state.in_vec),
                 );
                 let out_response = ui.add(
-                    self.out_plug
+                    out_plug
                         .id(PlugId::new(self.id, PlugType::Out))
                         .default_pos_no_overwrite(default_out_pos)
                         .cable_active(cable_state.active)
                         .vec(cable_state.out_vec),
                 );
 
-                // Given positions
-                let in_pos = in_response.rect.center();
-                let out_pos = out_response.rect.center();
+                let plug_state = State::get(ui);
+                let armed_plug_id = plug_state.armed_plug().map(|armed| armed.plug_id);
+                let in_armed = armed_plug_id == Some(PlugId::new(self.id, PlugType::In));
+                let out_armed = armed_plug_id == Some(PlugId::new(self.id, PlugType::Out));
+
+                // Given positions, the lines of resting plugs may end at the port anchor
+                let line_end = |port_id: &Option<PortId>, plug: &egui::Response, armed: bool| {
+                    let center = plug.rect.center();
+                    if armed || plug.dragged() {
+                        return center;
+                    }
+                    port_id
+                        .as_ref()
+                        .and_then(|port_id| plug_state.port_anchor(port_id))
+                        .and_then(|anchor| anchor.line_offset(plug.rect.size()))
+                        .map_or(center, |offset| center + offset)
+                };
+                let in_pos = line_end(&ends.in_port, &in_response, in_armed);
+                let out_pos = line_end(&ends.out_port, &out_response, out_armed);
                 let midpoint = (in_pos.to_vec2() + out_pos.to_vec2()) / 2.0;
 
-                let bezier_control_pos =
+                // a relative offset follows the ends as they move
+                if let Some(offset) = cable_state
+                    .relative_offset
+                    .filter(|_| settings.relative_curves)
+                    .and_then(|relative| from_relative(relative, in_pos, out_pos))
+                {
+                    cable_state.bezier_control_point_offset = offset;
+                }
+
+                let mut bezier_control_pos =
                     (midpoint + cable_state.bezier_control_point_offset).to_pos2();
+                // bend around the nodes on the canvas, except while the user shapes the curve
+                if !cable_state.dragged {
+                    let obstacles = plug_state
+                        .node_rects()
+                        .into_iter()
+                        .filter(|node| node.canvas == settings.id)
+                        .map(|node| node.rect)
+                        .collect::<Vec<_>>();
+                    bezier_control_pos =
+                        route_around(in_pos, bezier_control_pos, out_pos, &obstacles);
+                }
 
                 let bezier = QuadraticBezierShape::from_points_stroke(
                     [in_pos, bezier_control_pos, out_pos],
@@ -122,39 +268,65 @@ impl Widget for Cable {
                     (1.0, Color32::BLACK),
                 );
 
-                let pointer_pos = ui.input(|input| input.pointer.interact_pos());
+                let pointer_pos = ui
+                    .input(|input| input.pointer.interact_pos())
+                    .map(|pos| settings.to_canvas(pos));
+                // the tolerance is constant on the screen
+                let tolerance = 300.0 / settings.scaling().powi(2);
                 let is_close = pointer_pos
-                    .map(|pos| bezier_close(&bezier, pos, 300.0))
+                    .map(|pos| bezier_close(&bezier, pos, tolerance))
                     .unwrap_or(false);
 
                 let line_hovered = is_close || cable_state.dragged;
 
                 let plugs_interacted = in_response.hovered()
                     || in_response.dragged()
+                    || in_armed
                     || out_response.hovered()
-                    || out_response.dragged();
+                    || out_response.dragged()
+                    || out_armed;
 
                 let cable_control_pos = bezier.sample(0.5);
+                let path = CablePath {
+                    cable_id: self.id,
+                    canvas: settings.id,
+                    shape: if in_pos == out_pos {
+                        // same as the loop drawn by the default cable
+                        PathShape::Circle {
+                            center: Rect::from_two_pos(in_pos, cable_control_pos).center(),
+                            radius: cable_control_pos.distance(in_pos) / 2.0,
+                        }
+                    } else {
+                        PathShape::Bezier(bezier.points)
+                    },
+                };
 
                 CableParams {
                     active: cable_state.active,
                     line_hovered,
                     plugs_interacted,
+                    focused: cable_state.focused,
+                    context_menu_open: cable_state.context_menu_open,
                     cable_control: CableControl {
                         id: self.id,
                         pos: cable_control_pos,
                         widget: self.control_widget.unwrap_or_else(|| DefaultControl.into()),
                     },
                     bezier,
+                    color: self.color,
+                    locked: self.locked,
                 }
                 .set(ui);
                 let response = self.widget.unwrap_or_else(|| DefaultCable.into()).ui(ui);
 
-                if response.drag_started() {
+                if response.drag_started() && !self.locked {
                     cable_state.dragged = true;
+                    // start from the curve as drawn, bent around nodes or not
+                    cable_state.bezier_control_point_offset =
+                        bezier_control_pos - midpoint.to_pos2();
                     // drag_diff is used to prevent cable from jumping when cable is dragged.
                     if let Some(origin) = ui.input(|input| input.pointer.press_origin()) {
-                        cable_state.drag_offset = cable_control_pos - origin;
+                        cable_state.drag_offset = cable_control_pos - settings.to_canvas(origin);
                     } else {
                         // rare case
                         cable_state.drag_offset = vec2(0.0, 0.0);
@@ -163,28 +335,126 @@ impl Widget for Cable {
                 if response.drag_stopped() {
                     cable_state.dragged = false;
                 }
-                if response.dragged() {
+                if response.dragged() && cable_state.dragged {
                     if let Some(pointer_pos) = ui.input(|input| input.pointer.interact_pos()) {
+                        let pointer_pos = settings.to_canvas(pointer_pos);
                         // use drag_diff for prevent cable from jumping on click.
-                        cable_state.bezier_control_point_offset +=
-                            pointer_pos + cable_state.drag_offset - cable_control_pos;
+                        let target = pointer_pos + cable_state.drag_offset;
+                        if let Some(grid) = SnapGrid::current(ui.ctx()) {
+                            // the control is halfway between the midpoint and the control point
+                            cable_state.bezier_control_point_offset =
+                                (grid.snap(target) - midpoint.to_pos2()) * 2.0;
+                        } else {
+                            cable_state.bezier_control_point_offset += target - cable_control_pos;
+                        }
+                    }
+                    if settings.relative_curves {
+                        cable_state.relative_offset =
+                            to_relative(cable_state.bezier_control_point_offset, in_pos, out_pos);
                     }
                 }
+                if response.double_clicked() && !self.locked {
+                    cable_state.reset_curve();
+                }
 
-                // active or not
+                // selection
+                let modifiers = ui.input(|input| input.modifiers);
+                let extends = extends_selection(modifiers, settings.cable_deletion);
                 if response.clicked() {
-                    cable_state.active = true;
+                    if extends {
+                        if cable_state.active {
+                            remove_from_selection(ui.ctx(), canvas, self.id);
+                        } else {
+                            add_to_selection(ui.ctx(), canvas, self.id);
+                        }
+                    } else if !(settings.cable_deletion && modifiers.command) {
+                        select_cables_on(ui.ctx(), canvas, [self.id]);
+                    }
+                }
+                if deselected_by_click(&response) && !extends {
+                    remove_from_selection(ui.ctx(), canvas, self.id);
                 }
-                if response.clicked_elsewhere() {
-                    cable_state.active = false;
+                cable_state.active = is_cable_selected_on(ui.ctx(), canvas, self.id);
+
+                // keyboard
+                cable_state.focused = response.has_focus();
+
+                // opt-in deletion gestures
+                let mut delete_requested = false;
+                if settings.cable_deletion && !self.locked {
+                    // ctrl-click
+                    if response.clicked() && ui.input(|input| input.modifiers.command) {
+                        delete_requested = true;
+                    }
+                    // Delete on the focused cable
+                    if cable_state.focused && ui.input(|input| input.key_pressed(egui::Key::Delete))
+                    {
+                        delete_requested = true;
+                    }
+                    // Delete or Backspace on the active cable, unless the user is typing
+                    if cable_state.active
+                        && !ui.ctx().wants_keyboard_input()
+                        && ui.input(|input| {
+                            input.key_pressed(egui::Key::Delete)
+                                || input.key_pressed(egui::Key::Backspace)
+                        })
+                    {
+                        delete_requested = true;
+                    }
+                }
+
+                // context menu
+                let mut cable_event = None;
+                let mut disconnect_requested = false;
+                let show_defaults = settings.default_context_menus;
+                let show_delete = settings.cable_deletion && !self.locked;
+                let plugged = ends.in_port.is_some() || ends.out_port.is_some();
+                let defaults = (show_defaults || show_delete).then_some(|ui: &mut egui::Ui| {
+                    if show_defaults && !self.locked {
+                        if plugged && ui.button("Disconnect").clicked() {
+                            disconnect_requested = true;
+                            ui.close_menu();
+                        }
+                        if ui.button("Reset curve").clicked() {
+                            cable_state.reset_curve();
+                            ui.close_menu();
+                        }
+                        if ui.button("Swap ends").clicked() {
+                            cable_event = Some(Event::SwapEndsRequested { cable_id: self.id });
+                            ui.close_menu();
+                        }
+                    }
+                    if show_defaults {
+                        let label = if self.locked { "Unlock" } else { "Lock" };
+                        if ui.button(label).clicked() {
+                            cable_event = Some(Event::LockRequested {
+                                cable_id: self.id,
+                                lock: !self.locked,
+                            });
+                            ui.close_menu();
+                        }
+                    }
+                    if show_delete && ui.button("Delete").clicked() {
+                        delete_requested = true;
+                        ui.close_menu();
+                    }
+                });
+                cable_state.context_menu_open =
+                    context_menu::show(&response, self.context_menu, defaults);
+                // locking a selected cable locks the whole selection of the canvas
+                if let Some(Event::LockRequested { lock, .. }) = cable_event {
+                    if settings.id.is_some() && cable_state.active {
+                        ui.ctx()
+                            .data_mut(|data| data.insert_temp(canvas.with("lock-selection"), lock));
+                    }
                 }
 
                 // update plug vec state for rendering the plug
-                if in_response.dragged() {
+                if in_response.dragged() || in_armed {
                     cable_state.in_vec =
                         Some((bezier.sample(0.0) - bezier.sample(0.05)).normalized());
                 }
-                if out_response.dragged() {
+                if out_response.dragged() || out_armed {
                     cable_state.out_vec =
                         Some((bezier.sample(1.0) - bezier.sample(0.95)).normalized());
                 }
@@ -192,6 +462,32 @@ impl Widget for Cable {
                 // This must be after ui.add(plug) because state might be modified.
                 let mut state = State::get_cloned(ui);
 
+                if delete_requested {
+                    cable_event = Some(Event::DeleteRequested { cable_id: self.id });
+                }
+                if let Some(event) = cable_event {
+                    state.ephemeral.event_of_cable.insert(response.id, event);
+                }
+                if disconnect_requested {
+                    // plugged ends get the same event as dragging them away from their ports
+                    for (port, plug_response) in [
+                        (&ends.in_port, &in_response),
+                        (&ends.out_port, &out_response),
+                    ] {
+                        if port.is_some() {
+                            state
+                                .ephemeral
+                                .event_of_plug
+                                .insert(plug_response.id, Event::Disconnected);
+                        }
+                    }
+                }
+
+                // accessibility
+                describe_cable(&state, &response, &ends);
+                state.update_cable_ends(self.id, ends);
+                state.update_cable_path(path);
+
                 // this id is used in ResponseExt
                 state
                     .ephemeral
@@ -203,9 +499,36 @@ impl Widget for Cable {
                 state.store_to(ui);
 
                 response
-            })
-            .inner
+            },
+        )
+    }
+}
+
+/// Returns the layer to paint the cable on, or `None` for the foreground.
+///
+/// With [`CableLayering::Ports`], a cable resting on its ports goes on the upper layer of them.
+fn cable_layer(ctx: &egui::Context, ends: &CableEnds) -> Option<LayerId> {
+    if CanvasSettings::current(ctx).layering != CableLayering::Ports {
+        return None;
     }
+    let state = State::get_with_ctx(ctx);
+    let armed_plug_id = state.armed_plug().map(|armed| armed.plug_id);
+    let layers = [
+        (&ends.in_port, PlugType::In),
+        (&ends.out_port, PlugType::Out),
+    ]
+    .into_iter()
+    .filter_map(|(port_id, plug_type)| port_id.as_ref().map(|port_id| (port_id, plug_type)))
+    .map(|(port_id, plug_type)| {
+        let plug_id = PlugId::new(ends.cable_id, plug_type);
+        let resting = armed_plug_id.as_ref() != Some(&plug_id)
+            && state
+                .plug_state(&plug_id)
+                .map_or(true, |plug_state| plug_state.is_resting());
+        resting.then(|| state.port_layer(port_id)).flatten()
+    })
+    .collect::<Option<Vec<_>>>()?;
+    layers.into_iter().reduce(|a, b| upper_layer(ctx, a, b))
 }
 
 fn bezier_close(bezier: &QuadraticBezierShape, pointer_pos: Pos2, distance_sq: f32) -> bool {
@@ -222,6 +545,7 @@ mod tests {
     use epaint::Color32;
 
     use super::*;
+    use crate::test_utils::*;
 
     #[test]
     fn test_bezier_close() {
@@ -234,4 +558,230 @@ mod tests {
         assert!(!bezier_close(&bezier, pos2(10.0, 10.0), 5.0));
         assert!(bezier_close(&bezier, pos2(10.0, 18.0), 5.0));
     }
+
+    #[test]
+    fn relative_offset() {
+        let offset = vec2(10.0, 30.0);
+        let relative = to_relative(offset, pos2(0.0, 0.0), pos2(100.0, 0.0)).unwrap();
+        let close = |a: Option<Vec2>, b: Vec2| (a.unwrap() - b).length() < 1e-3;
+        assert!(close(
+            from_relative(relative, pos2(0.0, 0.0), pos2(100.0, 0.0)),
+            offset
+        ));
+        // twice as long and turned by 90 degrees
+        assert!(close(
+            from_relative(relative, pos2(0.0, 0.0), pos2(0.0, 200.0)),
+            vec2(-60.0, 20.0)
+        ));
+        assert_eq!(to_relative(offset, pos2(0.0, 0.0), pos2(0.0, 0.0)), None);
+    }
+
+    /// Renders a cable between two ports and returns the control rect and whether deletion is requested.
+    fn run(
+        ctx: &egui::Context,
+        modifiers: egui::Modifiers,
+        events: Vec<egui::Event>,
+    ) -> (Rect, bool) {
+        let canvas = CableCanvas::new("canvas").cable_deletion(true);
+        run_canvas(ctx, canvas, modifiers, events, |ui| {
+            two_ports(ui);
+            let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+            (response.rect, response.delete_requested())
+        })
+        .inner
+    }
+
+    #[test]
+    fn deletion_gestures() {
+        let ctx = egui::Context::default();
+        run(&ctx, Default::default(), vec![]);
+        let (rect, _) = run(&ctx, Default::default(), vec![]);
+        let pos = rect.center();
+        let hover = vec![egui::Event::PointerMoved(pos)];
+        run(&ctx, Default::default(), hover.clone());
+        run(&ctx, Default::default(), hover);
+
+        // ctrl-click
+        let (_, deleted) = run(
+            &ctx,
+            egui::Modifiers::COMMAND,
+            click(pos, egui::Modifiers::COMMAND),
+        );
+        assert!(deleted);
+
+        // click to activate, then Backspace
+        let (_, deleted) = run(&ctx, Default::default(), click(pos, Default::default()));
+        assert!(!deleted);
+        let backspace = vec![key(egui::Key::Backspace, Default::default())];
+        let (_, deleted) = run(&ctx, Default::default(), backspace);
+        assert!(deleted);
+    }
+
+    /// Drags the control of the cable by the offset and returns its rect afterwards.
+    fn drag_control(ctx: &egui::Context, offset: Vec2) -> Rect {
+        let (rect, _) = run(ctx, Default::default(), vec![]);
+        let (from, to) = (rect.center(), rect.center() + offset);
+        run(
+            ctx,
+            Default::default(),
+            vec![egui::Event::PointerMoved(from)],
+        );
+        run(
+            ctx,
+            Default::default(),
+            vec![egui::Event::PointerMoved(from)],
+        );
+        run(ctx, Default::default(), vec![button(from, true)]);
+        for i in 1..=4 {
+            let pos = from + (to - from) * i as f32 / 4.0;
+            run(
+                ctx,
+                Default::default(),
+                vec![egui::Event::PointerMoved(pos)],
+            );
+        }
+        run(ctx, Default::default(), vec![button(to, false)]);
+        run(ctx, Default::default(), vec![]).0
+    }
+
+    #[test]
+    fn reset_curves() {
+        let ctx = egui::Context::default();
+        run(&ctx, Default::default(), vec![]);
+        let (initial, _) = run(&ctx, Default::default(), vec![]);
+        let offset = vec2(0.0, 60.0);
+        let dragged = drag_control(&ctx, offset);
+        assert!(dragged.center().y > initial.center().y + 30.0);
+
+        // double-click on the control
+        let pos = dragged.center();
+        run(&ctx, Default::default(), click(pos, Default::default()));
+        run(&ctx, Default::default(), click(pos, Default::default()));
+        let (reset, _) = run(&ctx, Default::default(), vec![]);
+        assert_eq!(reset, initial);
+
+        // by the app
+        assert_ne!(drag_control(&ctx, offset), initial);
+        reset_curve(&ctx, CableId::new(0));
+        let (reset, _) = run(&ctx, Default::default(), vec![]);
+        assert_eq!(reset, initial);
+    }
+
+    #[test]
+    fn delete_key_needs_opt_in() {
+        for deletion in [false, true] {
+            let ctx = egui::Context::default();
+            let run = |events| {
+                let canvas = CableCanvas::new("canvas").cable_deletion(deletion);
+                run_canvas(&ctx, canvas, Default::default(), events, |ui| {
+                    two_ports(ui);
+                    let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+                    (response.id, response.delete_requested())
+                })
+                .inner
+            };
+            run(vec![]);
+            let (id, _) = run(vec![]);
+            ctx.memory_mut(|memory| memory.request_focus(id));
+            run(vec![]);
+            let (_, deleted) = run(vec![key(egui::Key::Delete, Default::default())]);
+            assert_eq!(deleted, deletion);
+        }
+    }
+
+    #[test]
+    fn cables_on_the_window_in_front() {
+        let ctx = egui::Context::default();
+        let window = |name| LayerId::new(egui::Order::Middle, Id::new(name));
+        // returns the layers of the cable and its plugs
+        let run = || {
+            let canvas = CableCanvas::new("canvas").layering(CableLayering::Ports);
+            run_canvas(&ctx, canvas, Default::default(), vec![], |ui| {
+                for (port, pos) in [(0, pos2(100.0, 100.0)), (1, pos2(150.0, 150.0))] {
+                    egui::Window::new(format!("window {port}"))
+                        .fixed_pos(pos)
+                        .fixed_size(vec2(100.0, 100.0))
+                        .show(ui.ctx(), |ui| ui.add(Port::new(port)));
+                }
+                let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+                let layers = [response.in_plug().layer_id, response.out_plug().layer_id];
+                vec![response.layer_id, layers[0], layers[1]]
+            })
+            .inner
+        };
+        run();
+        // the window shown later is in front
+        assert_eq!(
+            run(),
+            vec![window("window 1"), window("window 0"), window("window 1")]
+        );
+        ctx.move_to_top(window("window 0"));
+        run();
+        assert_eq!(
+            run(),
+            vec![window("window 0"), window("window 0"), window("window 1")]
+        );
+    }
+
+    /// Renders two locked cables and returns the control rects and the locks requested on the canvas.
+    fn run_locked(
+        ctx: &egui::Context,
+        events: Vec<egui::Event>,
+    ) -> (Vec<Rect>, Vec<(CableId, bool)>) {
+        let canvas = CableCanvas::new("canvas").default_context_menus(true);
+        let response = run_canvas(ctx, canvas, Default::default(), events, |ui| {
+            put_ports(
+                ui,
+                [
+                    (0, pos2(100.0, 100.0)),
+                    (1, pos2(300.0, 100.0)),
+                    (2, pos2(100.0, 300.0)),
+                    (3, pos2(300.0, 300.0)),
+                ],
+            );
+            [(0, 1), (2, 3)]
+                .into_iter()
+                .enumerate()
+                .map(|(i, (from, to))| {
+                    ui.add(Cable::new(i, Plug::to(from), Plug::to(to)).lock())
+                        .rect
+                })
+                .collect()
+        });
+        let locks = response.locks_requested().collect();
+        (response.inner, locks)
+    }
+
+    #[test]
+    fn unlock_the_selection() {
+        let ctx = egui::Context::default();
+        run_locked(&ctx, vec![]);
+        let (rects, _) = run_locked(&ctx, vec![]);
+        crate::selection::select_cables(&ctx, "canvas", [Id::new(0), Id::new(1)]);
+        // open the context menu of the second cable, which only has "Unlock"
+        let pos = rects[1].center();
+        let secondary = |pressed| {
+            pointer_button(
+                pos,
+                egui::PointerButton::Secondary,
+                pressed,
+                Default::default(),
+            )
+        };
+        run_locked(&ctx, vec![egui::Event::PointerMoved(pos)]);
+        run_locked(&ctx, vec![egui::Event::PointerMoved(pos)]);
+        run_locked(&ctx, vec![secondary(true)]);
+        run_locked(&ctx, vec![secondary(false)]);
+        run_locked(&ctx, vec![]);
+        // the menu opens below and right of the pointer
+        let menu = ctx
+            .layer_id_at(pos + vec2(10.0, 10.0))
+            .and_then(|layer| ctx.memory(|memory| memory.area_rect(layer.id)))
+            .unwrap();
+        let entry = menu.center();
+        run_locked(&ctx, vec![egui::Event::PointerMoved(entry)]);
+        run_locked(&ctx, vec![button(entry, true)]);
+        let (_, locks) = run_locked(&ctx, vec![button(entry, false)]);
+        assert_eq!(locks, vec![(Id::new(0), false), (Id::new(1), false)]);
+    }
 }
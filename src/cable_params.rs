@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use egui::Id;
-use epaint::QuadraticBezierShape;
+use epaint::{Color32, QuadraticBezierShape};
 
 use crate::cable_control::CableControl;
 
@@ -10,8 +10,16 @@ pub struct CableParams {
     pub active: bool,
     pub line_hovered: bool,
     pub plugs_interacted: bool,
+    /// The cable control has keyboard focus.
+    pub focused: bool,
+    /// The context menu of the cable control is open.
+    pub context_menu_open: bool,
     pub cable_control: CableControl,
     pub bezier: QuadraticBezierShape,
+    /// Given by [`Cable::color`](crate::cable::Cable::color).
+    pub color: Option<Color32>,
+    /// Given by [`Cable::lock`](crate::cable::Cable::lock).
+    pub locked: bool,
 }
 
 impl CableParams {
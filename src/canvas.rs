@@ -0,0 +1,524 @@
+use std::hash::Hash;
+use std::sync::Arc;
+
+use egui::{emath::TSTransform, Id, Order, PointerButton, Pos2, Rect, Response, Ui};
+
+use crate::{auto_pan, event::Event, grid::SnapGrid, knife::knife, node, selection};
+
+const MIN_ZOOM: f32 = 0.1;
+const MAX_ZOOM: f32 = 10.0;
+
+/// How a plug is carried from one port to another.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ConnectionMode {
+    /// Press on a plug, drag it and release it on a port.
+    #[default]
+    Drag,
+    /// Click a plug (or a port it is plugged into) to arm it, then click a port to plug it in.
+    /// Escape cancels the gesture.
+    /// Dragging plugs still works in this mode.
+    ClickClick,
+}
+
+/// How plugs on the same port are laid out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PlugFan {
+    /// Plugs are stacked on the port. The top one shows the number of plugs,
+    /// and clicking the number brings the next one to the top.
+    #[default]
+    Stack,
+    /// Plugs are placed around the port.
+    Radial,
+    /// Plugs are placed side by side centered on the port.
+    Row,
+}
+
+/// Where cables and plugs are painted relative to windows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CableLayering {
+    /// Above everything, even above windows in front of their ports.
+    #[default]
+    Foreground,
+    /// On the layers of their ports, so windows in front of the ports hide them too.
+    /// A cable between two windows goes on the one in front.
+    /// Plugs and cables being carried stay in the foreground until they rest on ports,
+    /// and stacked plugs keep the rendering order instead of bringing the top one forward.
+    Ports,
+}
+
+/// The region cables are painted in. Plugs on ports out of it are held at its edge.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum CableClip {
+    /// Cables are not clipped, except to the canvas when it pans and zooms.
+    /// Plugs on ports scrolled out of their scroll areas are still held at the edge of the view.
+    #[default]
+    None,
+    /// The rect available to the canvas.
+    Canvas,
+    Rect(Rect),
+}
+
+/// A scope that configures every port, plug and cable rendered inside it.
+#[derive(Debug, Clone)]
+pub struct CableCanvas {
+    id: Id,
+    clip: CableClip,
+    pan_zoom: bool,
+    settings: CanvasSettings,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CanvasSettings {
+    // None outside of any canvas
+    pub id: Option<Id>,
+    pub connection_mode: ConnectionMode,
+    pub cable_deletion: bool,
+    pub knife: bool,
+    pub box_select: bool,
+    pub default_context_menus: bool,
+    pub relative_curves: bool,
+    pub plug_fan: PlugFan,
+    pub layering: CableLayering,
+    pub auto_pan: bool,
+    pub grid: Option<SnapGrid>,
+    pub snap_radius: Option<f32>,
+    // Some if the canvas is panned and zoomed
+    pub transform: Option<TSTransform>,
+    // resolved from `CableClip` when the canvas is shown
+    pub clip_rect: Option<Rect>,
+    // the clip rect of the contents; ports in a scroll area within the canvas have a narrower one
+    pub contents_clip: Option<Rect>,
+}
+
+/// Where a canvas was shown in the last frame.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Viewport {
+    /// The canvas rect on the screen.
+    pub rect: Rect,
+    pub transform: Option<TSTransform>,
+}
+
+impl Viewport {
+    /// Returns the canvas rect in the coordinates of the contents.
+    pub fn visible(&self) -> Rect {
+        self.transform
+            .map_or(self.rect, |transform| transform.inverse() * self.rect)
+    }
+
+    pub fn get(ctx: &egui::Context, canvas: Id) -> Option<Self> {
+        ctx.data(|data| data.get_temp(canvas.with("viewport")))
+    }
+}
+
+/// The result of [`CableCanvas::show`].
+pub struct CanvasResponse<R> {
+    pub inner: R,
+    pub response: Response,
+    /// Events of the canvas itself, like [`Event::CablesCut`].
+    pub events: Vec<Event>,
+    /// Maps the contents onto the screen. The identity unless [`CableCanvas::pan_zoom`] is enabled.
+    pub transform: TSTransform,
+}
+
+impl<R> CanvasResponse<R> {
+    /// Returns the cables cut by a knife stroke in this frame.
+    pub fn cables_cut(&self) -> Option<&[crate::cable::CableId]> {
+        self.events.iter().find_map(|event| match event {
+            Event::CablesCut { cable_ids } => Some(cable_ids.as_slice()),
+            _ => None,
+        })
+    }
+
+    /// Returns the cables to lock or unlock in this frame, with `true` to lock them.
+    /// "Lock" or "Unlock" in the context menu of a selected cable asks it for every selected cable.
+    pub fn locks_requested(&self) -> impl Iterator<Item = (crate::cable::CableId, bool)> + '_ {
+        self.events.iter().filter_map(|event| match event {
+            Event::LockRequested { cable_id, lock } => Some((*cable_id, *lock)),
+            _ => None,
+        })
+    }
+
+    /// Returns the nodes dropped after dragging in this frame.
+    pub fn nodes_moved(&self) -> Option<&[crate::node::NodeId]> {
+        self.events.iter().find_map(|event| match event {
+            Event::NodesMoved { node_ids } => Some(node_ids.as_slice()),
+            _ => None,
+        })
+    }
+}
+
+impl CableCanvas {
+    pub fn new(id_source: impl Hash) -> Self {
+        let id = Id::new(id_source);
+        CableCanvas {
+            id,
+            clip: CableClip::None,
+            pan_zoom: false,
+            settings: CanvasSettings {
+                id: Some(id),
+                ..Default::default()
+            },
+        }
+    }
+
+    pub fn connection_mode(mut self, mode: ConnectionMode) -> Self {
+        self.settings.connection_mode = mode;
+        self
+    }
+
+    /// Enables deletion gestures: ctrl-click on a cable control, Delete on the focused cable,
+    /// Delete or Backspace on the active cable, and "Delete" in the context menu of a cable control.
+    /// Each of them emits [`Event::DeleteRequested`](crate::event::Event::DeleteRequested) and the app removes the cable.
+    pub fn cable_deletion(mut self, enabled: bool) -> Self {
+        self.settings.cable_deletion = enabled;
+        self
+    }
+
+    /// Enables the knife: dragging with the secondary button while holding ctrl (cmd on macOS)
+    /// draws a cut line, and releasing it emits [`Event::CablesCut`] with every cable the line crosses.
+    /// The line starts off clickable widgets, so a press on a cable control still opens its context menu.
+    pub fn knife(mut self, enabled: bool) -> Self {
+        self.settings.knife = enabled;
+        self
+    }
+
+    /// Enables rubber-band selection: dragging on the background of the canvas selects every cable touching the box.
+    /// Holding shift adds them to the current selection. See [`crate::selection`].
+    pub fn box_select(mut self, enabled: bool) -> Self {
+        self.settings.box_select = enabled;
+        self
+    }
+
+    /// Adds built-in entries to the context menus of cables, plugs and ports:
+    /// "Disconnect", "Reset curve", "Swap ends" and "Lock"/"Unlock" on cables,
+    /// "Disconnect" on plugs and "Disconnect all" on ports.
+    /// Swapping and locking are emitted as events because the app owns those settings.
+    pub fn default_context_menus(mut self, enabled: bool) -> Self {
+        self.settings.default_context_menus = enabled;
+        self
+    }
+
+    /// Stores the curve made by dragging a cable control relative to the ends of the cable,
+    /// so the shape stretches and rotates with them instead of keeping the absolute offset.
+    pub fn relative_curves(mut self, enabled: bool) -> Self {
+        self.settings.relative_curves = enabled;
+        self
+    }
+
+    pub fn plug_fan(mut self, fan: PlugFan) -> Self {
+        self.settings.plug_fan = fan;
+        self
+    }
+
+    pub fn layering(mut self, layering: CableLayering) -> Self {
+        self.settings.layering = layering;
+        self
+    }
+
+    /// Clips cables to a region. Ports out of their scroll areas are always treated as out of view,
+    /// and their plugs are held at the edge of the view with an arrow toward the port.
+    pub fn clip_cables(mut self, clip: CableClip) -> Self {
+        self.clip = clip;
+        self
+    }
+
+    /// Enables panning by dragging the background (with the middle button if box select is enabled) or scrolling,
+    /// and zooming by ctrl-scroll or pinch.
+    /// Ports, plugs and cables are laid out in the coordinates of the contents, which match the screen before panning,
+    /// so cable curves are kept in those coordinates too. Cables are clipped to the canvas.
+    pub fn pan_zoom(mut self, enabled: bool) -> Self {
+        self.pan_zoom = enabled;
+        self
+    }
+
+    /// Scrolls toward a plug dragged near the edge of the view, faster the closer it gets,
+    /// so it can reach ports out of the view.
+    /// Ports in a [`egui::ScrollArea`] scroll it, and a canvas with [`Self::pan_zoom`] pans its contents.
+    pub fn auto_pan(mut self, enabled: bool) -> Self {
+        self.settings.auto_pan = enabled;
+        self
+    }
+
+    /// Lets ports take plugs carried within the radius, in points on the screen, instead of only plugs overlapping them.
+    /// A plug jumps onto the nearest port in reach while it is carried, and is plugged into it when dropped.
+    pub fn snap_radius(mut self, radius: f32) -> Self {
+        self.settings.snap_radius = Some(radius);
+        self
+    }
+
+    /// Snaps unplugged plugs, cable controls and nodes to a grid, and paints it behind the contents.
+    pub fn grid(mut self, grid: SnapGrid) -> Self {
+        self.settings.grid = Some(grid);
+        self
+    }
+
+    pub fn show<R>(
+        mut self,
+        ui: &mut Ui,
+        add_contents: impl FnOnce(&mut Ui) -> R,
+    ) -> CanvasResponse<R> {
+        let id = self.id;
+        let ctx = ui.ctx().clone();
+        let rect = ui.max_rect().intersect(ui.clip_rect());
+        let knife_enabled = self.settings.knife;
+        let box_select_enabled = self.settings.box_select;
+        let cable_deletion = self.settings.cable_deletion;
+        let auto_pan_enabled = self.settings.auto_pan;
+        let grid = self.settings.grid;
+        let transform = self.pan_zoom.then(|| canvas_transform(&ctx, id));
+        // the canvas rect in the coordinates of the contents
+        let viewport = Viewport { rect, transform };
+        ctx.data_mut(|data| data.insert_temp(id.with("viewport"), viewport));
+        let visible = viewport.visible();
+        self.settings.transform = transform;
+        self.settings.contents_clip = Some(if transform.is_some() {
+            visible
+        } else {
+            ui.clip_rect()
+        });
+        self.settings.clip_rect = match self.clip {
+            // a panned canvas clips cables anyway
+            CableClip::None => transform.map(|_| visible),
+            CableClip::Canvas => Some(visible),
+            CableClip::Rect(clip_rect) => Some(clip_rect),
+        };
+
+        let contents = |ui: &mut Ui| {
+            if let Some(grid) = grid {
+                grid.paint(
+                    ui,
+                    visible,
+                    transform.map_or(1.0, |transform| transform.scaling),
+                );
+            }
+            let background = (box_select_enabled || transform.is_some())
+                .then(|| selection::background(ui, id, visible));
+            let inner = ui.push_id(id, add_contents);
+            if let Some(background) = background.as_ref().filter(|_| box_select_enabled) {
+                selection::box_select(ui, id, background, cable_deletion);
+            }
+            (inner, background)
+        };
+        node::update_group_drag(&ctx, id, viewport);
+        // Windows and areas shown inside the closure are rendered synchronously,
+        // so a stack in the context data is enough to scope the settings.
+        push_settings(&ctx, self.settings);
+        let (inner, background) = if let Some(transform) = transform {
+            // The contents go on an area of their own to be transformed.
+            let area = egui::Area::new(id.with("contents"))
+                .order(Order::Middle)
+                .current_pos(rect.min)
+                .movable(false)
+                .interactable(false)
+                .show(&ctx, |ui| {
+                    ui.set_clip_rect(visible);
+                    ui.allocate_ui_at_rect(rect, contents).inner
+                });
+            ctx.set_transform_layer(area.response.layer_id, transform);
+            area.inner
+        } else {
+            contents(ui)
+        };
+        pop_settings(&ctx);
+
+        if let Some(transform) = transform {
+            let mut panned = pan_zoom(ui, rect, transform, background.as_ref(), box_select_enabled);
+            if auto_pan_enabled {
+                panned = auto_pan::pan_toward_plugs(&ctx, id, rect, panned);
+            }
+            set_transform(&ctx, id, panned);
+        }
+
+        let mut events = vec![];
+        if let Some(node_ids) = node::take_moved_nodes(&ctx, id) {
+            events.push(Event::NodesMoved { node_ids });
+        }
+        if let Some(lock) = ctx.data_mut(|data| data.remove_temp(id.with("lock-selection"))) {
+            for cable_id in selection::selected_cables_on(&ctx, id) {
+                events.push(Event::LockRequested { cable_id, lock });
+            }
+        }
+        if knife_enabled {
+            if let Some(cable_ids) = knife(&ctx, id, rect, transform) {
+                events.push(Event::CablesCut { cable_ids });
+            }
+        }
+        CanvasResponse {
+            inner: inner.inner,
+            response: inner.response,
+            events,
+            transform: transform.unwrap_or_default(),
+        }
+    }
+}
+
+/// Sets the pan and zoom of the canvas with the ID source, mapping the contents onto the screen.
+pub fn set_canvas_transform(ctx: &egui::Context, id_source: impl Hash, transform: TSTransform) {
+    set_transform(ctx, Id::new(id_source), transform);
+}
+
+pub(crate) fn set_transform(ctx: &egui::Context, canvas: Id, transform: TSTransform) {
+    ctx.data_mut(|data| data.insert_temp(canvas.with("transform"), transform));
+}
+
+fn canvas_transform(ctx: &egui::Context, id: Id) -> TSTransform {
+    ctx.data(|data| data.get_temp(id.with("transform")).unwrap_or_default())
+}
+
+/// Pans by dragging the background or scrolling, and zooms by ctrl-scroll or pinch around the pointer.
+/// Dragging the background with the primary button selects cables instead if box select is enabled.
+fn pan_zoom(
+    ui: &Ui,
+    rect: Rect,
+    mut transform: TSTransform,
+    background: Option<&Response>,
+    box_select: bool,
+) -> TSTransform {
+    if let Some(background) = background {
+        if background.dragged_by(PointerButton::Middle)
+            || !box_select && background.dragged_by(PointerButton::Primary)
+        {
+            // the drag delta is in the coordinates of the contents
+            let delta = background.drag_delta() * transform.scaling;
+            transform = TSTransform::from_translation(delta) * transform;
+        }
+    }
+    if !ui.rect_contains_pointer(rect) {
+        return transform;
+    }
+    let (scroll, zoom, pointer) = ui.input(|input| {
+        (
+            input.smooth_scroll_delta,
+            input.zoom_delta(),
+            input.pointer.hover_pos(),
+        )
+    });
+    transform = TSTransform::from_translation(scroll) * transform;
+    if let Some(pointer) = pointer.filter(|_| zoom != 1.0) {
+        let zoom = (transform.scaling * zoom).clamp(MIN_ZOOM, MAX_ZOOM) / transform.scaling;
+        // zoom around the pointer
+        transform = TSTransform::from_translation(pointer.to_vec2())
+            * TSTransform::from_scaling(zoom)
+            * TSTransform::from_translation(-pointer.to_vec2())
+            * transform;
+    }
+    transform
+}
+
+impl CanvasSettings {
+    /// Converts a position on the screen into the coordinates of the contents.
+    pub(crate) fn to_canvas(&self, pos: Pos2) -> Pos2 {
+        self.transform
+            .map_or(pos, |transform| transform.inverse() * pos)
+    }
+
+    /// Returns the zoom of the contents.
+    pub(crate) fn scaling(&self) -> f32 {
+        self.transform.map_or(1.0, |transform| transform.scaling)
+    }
+
+    /// Returns the settings of the innermost canvas, or the defaults outside of any canvas.
+    pub(crate) fn current(ctx: &egui::Context) -> Self {
+        ctx.data(|data| {
+            data.get_temp::<Arc<Vec<CanvasSettings>>>(Id::NULL)
+                .and_then(|stack| stack.last().cloned())
+                .unwrap_or_default()
+        })
+    }
+}
+
+fn push_settings(ctx: &egui::Context, settings: CanvasSettings) {
+    ctx.data_mut(|data| {
+        let stack = data.get_temp_mut_or_default::<Arc<Vec<CanvasSettings>>>(Id::NULL);
+        Arc::make_mut(stack).push(settings);
+    });
+}
+
+fn pop_settings(ctx: &egui::Context) {
+    ctx.data_mut(|data| {
+        let stack = data.get_temp_mut_or_default::<Arc<Vec<CanvasSettings>>>(Id::NULL);
+        Arc::make_mut(stack).pop();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, vec2};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn settings_are_scoped() {
+        let ctx = egui::Context::default();
+        run_frame(&ctx, Default::default(), vec![], |ctx, ui| {
+            assert_eq!(
+                CanvasSettings::current(ctx).connection_mode,
+                ConnectionMode::Drag
+            );
+            CableCanvas::new("outer")
+                .connection_mode(ConnectionMode::ClickClick)
+                .show(ui, |ui| {
+                    assert_eq!(
+                        CanvasSettings::current(ui.ctx()).connection_mode,
+                        ConnectionMode::ClickClick
+                    );
+                    CableCanvas::new("inner").show(ui, |ui| {
+                        assert_eq!(
+                            CanvasSettings::current(ui.ctx()).connection_mode,
+                            ConnectionMode::Drag
+                        );
+                    });
+                    assert_eq!(
+                        CanvasSettings::current(ui.ctx()).connection_mode,
+                        ConnectionMode::ClickClick
+                    );
+                });
+            assert_eq!(
+                CanvasSettings::current(ctx).connection_mode,
+                ConnectionMode::Drag
+            );
+        });
+    }
+
+    /// Renders a cable between two ports in a panned canvas and returns the in-plug response and the transform.
+    fn run_panned(ctx: &egui::Context, events: Vec<egui::Event>) -> (Response, TSTransform) {
+        let canvas = CableCanvas::new("canvas").pan_zoom(true);
+        let response = run_canvas(ctx, canvas, Default::default(), events, |ui| {
+            two_ports(ui);
+            let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+            let in_plug = response.in_plug();
+            (*in_plug).clone()
+        });
+        (response.inner, response.transform)
+    }
+
+    #[test]
+    fn plugs_in_panned_and_zoomed_canvas() {
+        let ctx = egui::Context::default();
+        let transform = TSTransform::new(vec2(50.0, 20.0), 2.0);
+        set_canvas_transform(&ctx, "canvas", transform);
+        run_panned(&ctx, vec![]);
+        let (plug, _) = run_panned(&ctx, vec![]);
+        // laid out in the coordinates of the contents
+        assert_eq!(plug.rect.min, pos2(100.0, 100.0));
+        // and hovered where it is on the screen
+        let on_screen = transform * plug.rect.center();
+        run_panned(&ctx, vec![egui::Event::PointerMoved(on_screen)]);
+        let (plug, _) = run_panned(&ctx, vec![egui::Event::PointerMoved(on_screen)]);
+        assert!(plug.hovered());
+    }
+
+    #[test]
+    fn zoom_around_the_pointer() {
+        let ctx = egui::Context::default();
+        let pointer = pos2(200.0, 150.0);
+        run_panned(&ctx, vec![egui::Event::PointerMoved(pointer)]);
+        run_panned(&ctx, vec![egui::Event::Zoom(2.0)]);
+        // the transform used for rendering the next frame
+        let (_, transform) = run_panned(&ctx, vec![]);
+        assert_eq!(transform.scaling, 2.0);
+        // the point under the pointer stays
+        assert!((transform * pointer - pointer).length() < 1e-3);
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt::Debug;
+
+use egui::{Id, LayerId, Pos2};
+
+/// Entries added by the app to the context menu of a cable, a plug or a port.
+pub(crate) struct ContextMenu(Box<dyn FnOnce(&mut egui::Ui)>);
+
+impl ContextMenu {
+    pub fn new(add_contents: impl FnOnce(&mut egui::Ui) + 'static) -> Self {
+        ContextMenu(Box::new(add_contents))
+    }
+
+    pub fn ui(self, ui: &mut egui::Ui) {
+        (self.0)(ui)
+    }
+}
+
+impl Debug for ContextMenu {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContextMenu").finish_non_exhaustive()
+    }
+}
+
+/// Shows the app entries and then the built-in entries separated by a line.
+/// Returns true if the menu is open.
+pub(crate) fn show(
+    response: &egui::Response,
+    custom: Option<ContextMenu>,
+    defaults: Option<impl FnOnce(&mut egui::Ui)>,
+) -> bool {
+    if custom.is_none() && defaults.is_none() {
+        return false;
+    }
+    // egui returns the inner response only in the frame the menu closes, so ask it whether the menu is open
+    response.context_menu(|ui| {
+        let layer_id = ui.layer_id();
+        ui.ctx()
+            .data_mut(|data| data.insert_temp(Id::NULL, MenuLayer(layer_id)));
+        let has_custom = custom.is_some();
+        if let Some(custom) = custom {
+            custom.ui(ui);
+        }
+        if let Some(defaults) = defaults {
+            if has_custom {
+                ui.separator();
+            }
+            defaults(ui);
+        }
+    });
+    response.context_menu_opened()
+}
+
+// The layer of the latest context menu shown, in the context data.
+// egui shows a single context menu at a time.
+#[derive(Debug, Clone, Copy)]
+struct MenuLayer(LayerId);
+
+/// Returns true if the position is on an open context menu.
+pub(crate) fn is_on_menu(ctx: &egui::Context, pos: Pos2) -> bool {
+    let menu = ctx.data(|data| data.get_temp::<MenuLayer>(Id::NULL));
+    menu.is_some_and(|menu| ctx.layer_id_at(pos) == Some(menu.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, Event, Modifiers, PointerButton, Pos2};
+
+    use crate::{prelude::*, state::State, test_utils::*};
+
+    /// Renders two cables from the same port and returns whether each in-plug is disconnected.
+    fn run(ctx: &egui::Context, events: Vec<Event>) -> [bool; 2] {
+        let canvas = CableCanvas::new("canvas").default_context_menus(true);
+        run_canvas(ctx, canvas, Modifiers::NONE, events, |ui| {
+            two_ports(ui);
+            let mut disconnected = [false; 2];
+            for (i, disconnected) in disconnected.iter_mut().enumerate() {
+                let mut response = ui.add(Cable::new(i, Plug::to(0), Plug::to(1)));
+                *disconnected = response.in_plug().disconnected();
+            }
+            disconnected
+        })
+        .inner
+    }
+
+    fn click(ctx: &egui::Context, pos: Pos2, button: PointerButton) -> [bool; 2] {
+        let event = |pressed| pointer_button(pos, button, pressed, Modifiers::NONE);
+        run(ctx, vec![Event::PointerMoved(pos)]);
+        run(ctx, vec![event(true)]);
+        run(ctx, vec![event(false)])
+    }
+
+    #[test]
+    fn disconnect_all_from_plug() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![]);
+        run(&ctx, vec![]);
+        click(&ctx, pos2(108.0, 108.0), PointerButton::Secondary);
+        let entry = State::get_with_ctx(&ctx)
+            .disconnect_all_button(&PortId::new(0))
+            .and_then(|button| ctx.read_response(button))
+            .expect("the context menu of the plug is open")
+            .rect;
+        // both plugs disconnect in the frame of the click, including the one added before the menu
+        assert_eq!(
+            click(&ctx, entry.center(), PointerButton::Primary),
+            [true, true]
+        );
+        assert_eq!(run(&ctx, vec![]), [false, false]);
+    }
+
+    /// Renders a cable between two ports and returns its rect and whether its context menu is open.
+    fn run_cable(ctx: &egui::Context, events: Vec<Event>) -> (egui::Rect, bool) {
+        let canvas = CableCanvas::new("canvas").default_context_menus(true);
+        run_canvas(ctx, canvas, Modifiers::NONE, events, |ui| {
+            two_ports(ui);
+            let response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+            (response.rect, response.context_menu_opened())
+        })
+        .inner
+    }
+
+    #[test]
+    fn cable_menu_stays_open_off_the_line() {
+        let ctx = egui::Context::default();
+        run_cable(&ctx, vec![]);
+        let (rect, _) = run_cable(&ctx, vec![]);
+        let pos = rect.center();
+        let secondary =
+            |pressed| pointer_button(pos, PointerButton::Secondary, pressed, Modifiers::NONE);
+        run_cable(&ctx, vec![Event::PointerMoved(pos)]);
+        run_cable(&ctx, vec![Event::PointerMoved(pos)]);
+        run_cable(&ctx, vec![secondary(true)]);
+        assert!(run_cable(&ctx, vec![secondary(false)]).1);
+        // the menu covers the line, so the pointer on the menu no longer hovers the cable
+        let on_menu = vec![Event::PointerMoved(pos + egui::vec2(20.0, 30.0))];
+        run_cable(&ctx, on_menu.clone());
+        assert!(run_cable(&ctx, on_menu).1);
+    }
+}
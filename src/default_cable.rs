@@ -15,17 +15,27 @@ impl Widget for DefaultCable {
         let active = params.active;
         let line_hovered = params.line_hovered;
         let plugs_interacted = params.plugs_interacted;
+        let focused = params.focused;
+        let context_menu_open = params.context_menu_open;
+        let color = params.color;
         let cable_control = params.cable_control;
         let mut bezier = params.bezier;
         let cable_control_pos = cable_control.pos;
 
         // We don't want to show cable control when cable is not hovered or a plug is interacted.
-        let response = if line_hovered && !plugs_interacted {
-            ui.add(cable_control)
+        // A focused control is always shown for keyboard users, and the control keeps its context menu.
+        let response = if line_hovered && !plugs_interacted || focused || context_menu_open {
+            let response = ui.add(cable_control);
+            // hand over the focus from the placeholder below
+            if focused && !response.has_focus() {
+                response.request_focus();
+            }
+            response
         } else {
+            // A placeholder keeps the cable reachable by Tab and arrow keys.
             ui.interact(
-                Rect::NOTHING,
-                egui::Id::new("dummy-cable-control"),
+                Rect::from_center_size(cable_control_pos, SIZE),
+                egui::Id::new((cable_control.id, "cable-control-placeholder")),
                 Sense::focusable_noninteractive(),
             )
         };
@@ -41,6 +51,9 @@ impl Widget for DefaultCable {
                 widget_visuals(ui, &response)
             };
             bezier.stroke = cable_visual.fg_stroke;
+            if let Some(color) = color {
+                bezier.stroke.color = color;
+            }
 
             // paint bezier curve or circle
             if in_pos == out_pos {
@@ -49,7 +62,7 @@ impl Widget for DefaultCable {
                 ui.painter().circle_stroke(
                     center,
                     cable_control_pos.distance(in_pos) / 2.0,
-                    cable_visual.fg_stroke,
+                    bezier.stroke,
                 );
             } else {
                 ui.painter().add(bezier);
@@ -70,6 +83,11 @@ impl Widget for DefaultControl {
             let visuals = widget_visuals(ui, &response);
             ui.painter()
                 .rect(rect, 3.0, visuals.bg_fill, visuals.fg_stroke);
+            if response.has_focus() {
+                // focus ring
+                ui.painter()
+                    .rect_stroke(rect.expand(2.0), 5.0, ui.visuals().selection.stroke);
+            }
         }
         response
     }
@@ -16,11 +16,18 @@ impl Widget for DefaultPlug {
         let active = params.active;
         let plugged = params.plugged;
         let locked = params.locked;
+        let armed = params.armed;
+        let context_menu = params.context_menu;
+        let hidden_port = params.hidden_port;
 
         let (rect, response) = ui.allocate_exact_size(
             SIZE,
             if (active || !plugged) && !locked {
-                Sense::drag()
+                // click is used for click-click connection
+                Sense::click_and_drag()
+            } else if context_menu {
+                // right-click opens the menu
+                Sense::click()
             } else {
                 // minimum sense to make not interactive
                 Sense::hover()
@@ -39,7 +46,7 @@ impl Widget for DefaultPlug {
             } else {
                 widget_visuals(ui, &response)
             };
-            if response.dragged() {
+            if response.dragged() || armed {
                 if let Some(vector) = vector {
                     ui.painter().arrow(
                         center_pos,
@@ -48,6 +55,14 @@ impl Widget for DefaultPlug {
                     );
                 }
             }
+            if let Some(direction) = hidden_port {
+                // points to the port out of view
+                ui.painter().arrow(
+                    center_pos + direction * size.x / 2.0,
+                    direction * size.x / 2.0,
+                    Stroke::new(2.0, visuals.fg_stroke.color),
+                );
+            }
             ui.painter().add(epaint::CircleShape {
                 center: center_pos,
                 radius: size.x / 2.0 * if active { 0.7 } else { 0.2 },
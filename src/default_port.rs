@@ -28,6 +28,14 @@ impl Widget for DefaultPort {
                 fill: visuals.bg_fill,
                 stroke: visuals.fg_stroke,
             });
+            if response.has_focus() {
+                // focus ring
+                ui.painter().circle_stroke(
+                    rect.center(),
+                    rect.height() / 2.0 + 2.0,
+                    ui.visuals().selection.stroke,
+                );
+            }
         }
 
         response
@@ -1,8 +1,36 @@
-use crate::prelude::*;
+use crate::{cable::CableId, node::NodeId, prelude::*};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Event {
-    Connected { port_id: PortId },
+    Connected {
+        port_id: PortId,
+    },
     Disconnected,
-    Hovered { port_id: PortId },
+    Hovered {
+        port_id: PortId,
+    },
+    /// The drag or the click-click gesture was aborted and the plug goes back to where it was.
+    DragCancelled,
+    /// The user asked to remove the cable. The app should remove it from its model.
+    DeleteRequested {
+        cable_id: CableId,
+    },
+    /// "Swap ends" is chosen in the context menu. The app should exchange the in-plug and the out-plug.
+    SwapEndsRequested {
+        cable_id: CableId,
+    },
+    /// "Lock" or "Unlock" is chosen in the context menu. The app should apply it with [`Cable::lock`](crate::cable::Cable::lock).
+    /// Chosen on a selected cable, the canvas also emits it for every selected cable.
+    LockRequested {
+        cable_id: CableId,
+        lock: bool,
+    },
+    /// A knife stroke crossed these cables. The app decides what to do with them, usually removes them.
+    CablesCut {
+        cable_ids: Vec<CableId>,
+    },
+    /// Dragging nodes ended. Their positions are given by [`node_pos`](crate::node::node_pos) for the app to persist.
+    NodesMoved {
+        node_ids: Vec<NodeId>,
+    },
 }
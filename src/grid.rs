@@ -0,0 +1,197 @@
+use egui::{pos2, Modifiers, Pos2, Rect, Shape, Stroke, Ui, Vec2};
+
+use crate::canvas::CanvasSettings;
+
+/// A grid that plugs, cable controls and [`Node`](crate::node::Node)s snap to on a canvas, see [`CableCanvas::grid`](crate::canvas::CableCanvas::grid).
+///
+/// Unplugged plugs snap their centers when they are dropped, controls snap while they are dragged,
+/// and nodes snap their top-left corners when they are dropped. Holding the bypass modifiers moves them freely.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SnapGrid {
+    spacing: f32,
+    background: bool,
+    bypass: Modifiers,
+}
+
+impl Default for SnapGrid {
+    fn default() -> Self {
+        SnapGrid {
+            spacing: 20.0,
+            background: true,
+            bypass: Modifiers::ALT,
+        }
+    }
+}
+
+impl SnapGrid {
+    /// A grid with the spacing in the coordinates of the canvas contents.
+    /// Spacings under one point, zero or negative included, are raised to one point.
+    pub fn new(spacing: f32) -> Self {
+        SnapGrid {
+            spacing: spacing.max(1.0),
+            ..Default::default()
+        }
+    }
+
+    /// Paints the grid lines behind the contents. Defaults to `true`.
+    pub fn background(mut self, background: bool) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// The modifiers that disable snapping while held. Defaults to alt (option on macOS).
+    pub fn bypass(mut self, modifiers: Modifiers) -> Self {
+        self.bypass = modifiers;
+        self
+    }
+
+    /// Returns the nearest grid point.
+    pub fn snap(&self, pos: Pos2) -> Pos2 {
+        (pos / self.spacing).round() * self.spacing
+    }
+
+    /// Returns the grid of the current canvas, unless the bypass modifiers are held.
+    pub(crate) fn current(ctx: &egui::Context) -> Option<Self> {
+        let grid = CanvasSettings::current(ctx).grid?;
+        let bypassed = ctx.input(|input| input.modifiers.contains(grid.bypass));
+        (!bypassed).then_some(grid)
+    }
+
+    /// Paints the lines crossing the visible rect, in the coordinates of the contents.
+    pub(crate) fn paint(&self, ui: &Ui, visible: Rect, scaling: f32) {
+        // too dense to see on the screen
+        if !self.background || self.spacing * scaling < 4.0 {
+            return;
+        }
+        let stroke = Stroke::new(
+            1.0 / scaling,
+            ui.visuals().widgets.noninteractive.bg_stroke.color,
+        );
+        let first = self.snap(visible.min);
+        let mut shapes = vec![];
+        let mut x = first.x;
+        while x <= visible.right() {
+            shapes.push(Shape::line_segment(
+                [pos2(x, visible.top()), pos2(x, visible.bottom())],
+                stroke,
+            ));
+            x += self.spacing;
+        }
+        let mut y = first.y;
+        while y <= visible.bottom() {
+            shapes.push(Shape::line_segment(
+                [pos2(visible.left(), y), pos2(visible.right(), y)],
+                stroke,
+            ));
+            y += self.spacing;
+        }
+        ui.painter_at(visible).extend(shapes);
+    }
+
+    /// Returns the top-left corner that puts the center of a rect of the size on the nearest grid point.
+    pub(crate) fn snap_center(&self, pos: Pos2, size: Vec2) -> Pos2 {
+        self.snap(pos + size / 2.0) - size / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{vec2, Event, PointerButton};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    /// Renders two ports, a cable between them and a cable from the first one on a canvas with a grid,
+    /// and returns the rects of the control of the first cable and the unplugged plug of the second one.
+    fn run(ctx: &egui::Context, events: Vec<Event>, modifiers: Modifiers) -> (Rect, Rect) {
+        let canvas = CableCanvas::new("canvas").grid(SnapGrid::new(20.0));
+        run_canvas(ctx, canvas, modifiers, events, |ui| {
+            put_ports(ui, [(0, pos2(103.0, 101.0)), (1, pos2(403.0, 101.0))]);
+            let control = ui.add(Cable::new(0, Plug::to(0), Plug::to(1))).rect;
+            let mut response = ui.add(Cable::new(1, Plug::to(0), Plug::unplugged()));
+            (control, response.out_plug().rect)
+        })
+        .inner
+    }
+
+    /// Drags from a point to another and returns the rects after the drop.
+    fn drag(ctx: &egui::Context, from: Pos2, to: Pos2, modifiers: Modifiers) -> (Rect, Rect) {
+        let button = |pos, pressed| pointer_button(pos, PointerButton::Primary, pressed, modifiers);
+        // the control shows up on hover and takes a frame to be placed
+        for _ in 0..2 {
+            run(ctx, vec![Event::PointerMoved(from)], modifiers);
+        }
+        run(ctx, vec![button(from, true)], modifiers);
+        for i in 1..=4 {
+            let pos = from + (to - from) * i as f32 / 4.0;
+            run(ctx, vec![Event::PointerMoved(pos)], modifiers);
+        }
+        run(ctx, vec![button(to, false)], modifiers);
+        run(ctx, vec![], Modifiers::NONE)
+    }
+
+    fn on_grid(pos: Pos2) -> bool {
+        pos.distance(SnapGrid::new(20.0).snap(pos)) < 0.01
+    }
+
+    #[test]
+    fn snap_plugs_and_controls() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![], Modifiers::NONE);
+        let (control, plug) = run(&ctx, vec![], Modifiers::NONE);
+
+        // the control snaps while it is dragged
+        let from = control.center();
+        let (control, _) = drag(&ctx, from, from + vec2(-31.0, 57.0), Modifiers::NONE);
+        assert!(on_grid(control.center()));
+
+        // the plug is dropped on the nearest grid point
+        let from = plug.center();
+        let (_, plug) = drag(&ctx, from, from + vec2(133.0, 47.0), Modifiers::NONE);
+        assert!(on_grid(plug.center()));
+
+        // but not while holding alt
+        let from = plug.center();
+        let (_, plug) = drag(&ctx, from, from + vec2(33.0, 7.0), Modifiers::ALT);
+        assert!(!on_grid(plug.center()));
+    }
+
+    #[test]
+    fn snap_nodes_on_drop() {
+        let ctx = egui::Context::default();
+        // returns the rect of the node
+        let run = |events| {
+            let canvas = CableCanvas::new("canvas").grid(SnapGrid::new(20.0));
+            run_canvas(&ctx, canvas, Modifiers::NONE, events, |ui| {
+                Node::new("node", "Node")
+                    .default_pos([103.0, 101.0])
+                    .show(ui.ctx(), |_| ())
+                    .unwrap()
+                    .response
+                    .rect
+            })
+            .inner
+        };
+        run(vec![]);
+        let node = run(vec![]);
+
+        // the top-left corner moves freely while dragging, and lands on the grid when dropped
+        let from = node.left_top() + vec2(40.0, 8.0);
+        let to = from + vec2(33.0, 47.0);
+        run(vec![Event::PointerMoved(from), button(from, true)]);
+        let dragged = run(vec![Event::PointerMoved(to)]);
+        assert!(!on_grid(dragged.min));
+        run(vec![button(to, false)]);
+        let dropped = run(vec![]);
+        assert!(on_grid(dropped.min));
+        assert!(dropped.min.distance(dragged.min) <= 10.0 * 2f32.sqrt());
+    }
+
+    #[test]
+    fn spacing_at_least_one_point() {
+        for spacing in [0.0, -20.0, f32::NAN] {
+            let snapped = SnapGrid::new(spacing).snap(pos2(10.4, 3.6));
+            assert_eq!(snapped, pos2(10.0, 4.0));
+        }
+    }
+}
@@ -0,0 +1,313 @@
+use std::hash::Hash;
+
+use egui::{
+    collapsing_header::paint_default_icon, vec2, Align2, Color32, FontId, Id, Pos2, Rect, Response,
+    Sense, Stroke, Ui, Vec2,
+};
+
+use crate::{
+    node::{move_nodes, node_pos, NodeId},
+    port::{self, PortId},
+    port_scope::{self, stand_in, StandIn},
+    state::State,
+};
+
+const TITLE_HEIGHT: f32 = 20.0;
+const MIN_SIZE: Vec2 = vec2(100.0, 2.0 * TITLE_HEIGHT);
+
+/// A titled and colored region behind the [`Node`](crate::node::Node)s shown in it.
+///
+/// Dragging the title bar moves the nodes shown in it, and the corner handle resizes it.
+/// The arrow in the title bar collapses it to the title bar. The contents are not shown then,
+/// and cables to their ports end at the left or the right edge of the title bar, whichever side the port was on.
+#[derive(Debug, Clone)]
+pub struct Group {
+    id: Id,
+    title: String,
+    color: Option<Color32>,
+    default_rect: Rect,
+    resizable: bool,
+}
+
+/// The result of [`Group::show`].
+pub struct GroupResponse<R> {
+    /// `None` while collapsed.
+    pub inner: Option<R>,
+    /// The title bar.
+    pub response: Response,
+    /// The region in the coordinates of the canvas contents, for the app to persist.
+    /// Only the title bar while collapsed.
+    pub rect: Rect,
+    pub collapsed: bool,
+}
+
+#[derive(Debug, Clone)]
+struct GroupState {
+    rect: Rect,
+    collapsed: bool,
+    // ports shown in the group while it was last expanded, and the edges they are on
+    members: Vec<(PortId, Align2)>,
+    // nodes shown in the group while it was last expanded
+    nodes: Vec<NodeId>,
+}
+
+impl Group {
+    pub fn new(id_source: impl Hash, title: impl Into<String>) -> Self {
+        Group {
+            id: Id::new(id_source),
+            title: title.into(),
+            color: None,
+            default_rect: Rect::from_min_size(Pos2::ZERO, vec2(300.0, 200.0)),
+            resizable: true,
+        }
+    }
+
+    /// Defaults to the selection color.
+    pub fn color(mut self, color: Color32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Sets the region the first time the group is shown.
+    pub fn default_rect(mut self, rect: Rect) -> Self {
+        self.default_rect = rect;
+        self
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Shows the region and the contents, usually nodes, on the ui of a canvas.
+    ///
+    /// Call this before showing other nodes, so nodes moved with the group don't lag behind.
+    pub fn show<R>(self, ui: &mut Ui, add_contents: impl FnOnce(&mut Ui) -> R) -> GroupResponse<R> {
+        let ctx = ui.ctx().clone();
+        let mut group = ctx
+            .data(|data| data.get_temp::<GroupState>(self.id))
+            .unwrap_or(GroupState {
+                rect: self.default_rect,
+                collapsed: false,
+                members: vec![],
+                nodes: vec![],
+            });
+
+        // the arrow is registered after the title bar to get clicks on it
+        let title_bar = Rect::from_min_size(group.rect.min, vec2(group.rect.width(), TITLE_HEIGHT));
+        let response = ui.interact(title_bar, self.id.with("title"), Sense::click_and_drag());
+        let arrow_rect = Rect::from_center_size(
+            title_bar.left_center() + vec2(TITLE_HEIGHT / 2.0, 0.0),
+            vec2(TITLE_HEIGHT, TITLE_HEIGHT) * 0.6,
+        );
+        let arrow = ui.interact(arrow_rect, self.id.with("collapse"), Sense::click());
+        if arrow.clicked() || response.double_clicked() {
+            group.collapsed = !group.collapsed;
+        }
+        let delta = response.drag_delta();
+        if delta != Vec2::ZERO {
+            let members = group
+                .nodes
+                .iter()
+                .filter_map(|node_id| Some((*node_id, node_pos(&ctx, *node_id)? + delta)))
+                .collect();
+            move_nodes(&ctx, members);
+            group.rect = group.rect.translate(delta);
+        }
+        if self.resizable && !group.collapsed {
+            let handle = Rect::from_min_size(group.rect.max - vec2(12.0, 12.0), vec2(12.0, 12.0));
+            let handle = ui.interact(handle, self.id.with("resize"), Sense::drag());
+            group.rect.max = (group.rect.max + handle.drag_delta()).max(group.rect.min + MIN_SIZE);
+            if handle.hovered() || handle.dragged() {
+                ctx.set_cursor_icon(egui::CursorIcon::ResizeNwSe);
+            }
+        }
+        let title_bar = Rect::from_min_size(group.rect.min, vec2(group.rect.width(), TITLE_HEIGHT));
+        let rect = if group.collapsed {
+            title_bar
+        } else {
+            group.rect
+        };
+
+        if ui.is_rect_visible(rect) {
+            let color = self.color.unwrap_or(ui.visuals().selection.bg_fill);
+            let painter = ui.painter();
+            painter.rect(
+                rect,
+                4.0,
+                color.linear_multiply(0.1),
+                Stroke::new(1.0, color),
+            );
+            painter.rect_filled(title_bar, 4.0, color.linear_multiply(0.4));
+            painter.text(
+                arrow_rect.right_center() + vec2(4.0, 0.0),
+                Align2::LEFT_CENTER,
+                &self.title,
+                FontId::proportional(14.0),
+                ui.visuals().strong_text_color(),
+            );
+            paint_default_icon(ui, if group.collapsed { 0.0 } else { 1.0 }, &arrow);
+        }
+
+        port_scope::open(&ctx);
+        let inner = (!group.collapsed).then(|| add_contents(ui));
+        let shown = port_scope::close(&ctx);
+
+        let mut state = State::get_cloned(ui);
+        if group.collapsed {
+            for (port_id, side) in &group.members {
+                let stand_in_at = StandIn {
+                    rect: title_bar,
+                    view: port::view(ui),
+                    anchor: (*side).into(),
+                    layer: ui.layer_id(),
+                };
+                stand_in(&mut state, &ctx, port_id, stand_in_at);
+            }
+        } else {
+            // the members are what is shown in the group, wherever it is
+            let side = |port_id: &PortId| match state.port_anchor(port_id) {
+                // the edge nearest to the port
+                Some(anchor) if anchor.rect.center().x > group.rect.center().x => {
+                    Align2::RIGHT_CENTER
+                }
+                _ => Align2::LEFT_CENTER,
+            };
+            group.members = shown
+                .ports
+                .into_iter()
+                .map(|port_id| (port_id.clone(), side(&port_id)))
+                .collect();
+            group.nodes = shown.nodes;
+        }
+        state.store_to(ui);
+
+        let collapsed = group.collapsed;
+        ctx.data_mut(|data| data.insert_temp(self.id, group));
+        GroupResponse {
+            inner,
+            response,
+            rect,
+            collapsed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, Event, Modifiers};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    /// Renders a group with a node in it and a port out of it connected to the node,
+    /// and returns the rects of the group, the node and the plug on the node.
+    fn run(ctx: &egui::Context, events: Vec<Event>) -> [Rect; 3] {
+        let canvas = CableCanvas::new("canvas");
+        run_canvas(ctx, canvas, Modifiers::NONE, events, |ui| {
+            let rect = Rect::from_min_size(pos2(100.0, 100.0), vec2(300.0, 200.0));
+            let group = Group::new("group", "Group")
+                .default_rect(rect)
+                .show(ui, |ui| {
+                    Node::new("node", "Node")
+                        .default_pos([150.0, 150.0])
+                        .input(Port::new(1), "in")
+                        .show(ui.ctx(), |_| ())
+                        .map(|node| node.response.rect)
+                });
+            put_ports(ui, [(0, pos2(600.0, 200.0))]);
+            let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+            [
+                group.rect,
+                group.inner.flatten().unwrap_or(Rect::NOTHING),
+                response.out_plug().rect,
+            ]
+        })
+        .inner
+    }
+
+    #[test]
+    fn drag_and_collapse() {
+        let ctx = egui::Context::default();
+        for _ in 0..3 {
+            run(&ctx, vec![]);
+        }
+        let [group, node, _] = run(&ctx, vec![]);
+
+        // the node moves with the title bar
+        let from = group.left_top() + vec2(100.0, 10.0);
+        let to = from + vec2(50.0, 30.0);
+        run(&ctx, vec![Event::PointerMoved(from), button(from, true)]);
+        let [moved_group, moved_node, _] = run(&ctx, vec![Event::PointerMoved(to)]);
+        run(&ctx, vec![button(to, false)]);
+        assert_eq!(moved_group.min - group.min, vec2(50.0, 30.0));
+        assert_eq!(moved_node.min - node.min, vec2(50.0, 30.0));
+
+        // the cable ends at the left edge of the collapsed group
+        let arrow = moved_group.left_top() + vec2(TITLE_HEIGHT / 2.0, TITLE_HEIGHT / 2.0);
+        run(&ctx, vec![Event::PointerMoved(arrow), button(arrow, true)]);
+        run(&ctx, vec![button(arrow, false)]);
+        let [collapsed, _, plug] = run(&ctx, vec![]);
+        assert_eq!(collapsed.height(), TITLE_HEIGHT);
+        assert!(plug.center().distance(collapsed.left_center()) < 1.0);
+    }
+
+    #[test]
+    fn members_are_shown_in_the_group() {
+        let ctx = egui::Context::default();
+        // returns the rects of the group, a node shown in the group out of its region,
+        // and a node shown over the region out of the group
+        let run = |in_group: bool, events| {
+            let canvas = CableCanvas::new("canvas");
+            run_canvas(&ctx, canvas, Modifiers::NONE, events, |ui| {
+                let member = |ui: &mut Ui| {
+                    Node::new("member", "Member")
+                        .default_pos([500.0, 150.0])
+                        .input(Port::new(0), "in")
+                        .show(ui.ctx(), |_| ())
+                        .unwrap()
+                        .response
+                        .rect
+                };
+                let rect = Rect::from_min_size(pos2(100.0, 100.0), vec2(300.0, 200.0));
+                let group = Group::new("group", "Group")
+                    .default_rect(rect)
+                    .show(ui, |ui| in_group.then(|| member(ui)));
+                let member = group.inner.flatten().unwrap_or_else(|| member(ui));
+                let other = Node::new("other", "Other")
+                    .default_pos([150.0, 150.0])
+                    .input(Port::new(1), "in")
+                    .show(ui.ctx(), |_| ())
+                    .unwrap()
+                    .response
+                    .rect;
+                [group.rect, member, other]
+            })
+            .inner
+        };
+        let members = || {
+            let group = ctx.data(|data| data.get_temp::<GroupState>(Id::new("group")));
+            let group = group.unwrap();
+            let ports: Vec<_> = group.members.into_iter().map(|(port, _)| port).collect();
+            (ports, group.nodes)
+        };
+        for _ in 0..3 {
+            run(true, vec![]);
+        }
+        let [group, member, other] = run(true, vec![]);
+        assert_eq!(members(), (vec![PortId::new(0)], vec![Id::new("member")]));
+
+        let from = group.left_top() + vec2(100.0, 10.0);
+        let to = from + vec2(50.0, 30.0);
+        run(true, vec![Event::PointerMoved(from), button(from, true)]);
+        run(true, vec![Event::PointerMoved(to)]);
+        run(true, vec![button(to, false)]);
+        let [_, moved_member, moved_other] = run(true, vec![]);
+        assert_eq!(moved_member.min - member.min, vec2(50.0, 30.0));
+        assert_eq!(moved_other, other);
+
+        // no longer shown in the group
+        run(false, vec![]);
+        assert_eq!(members(), (vec![], vec![]));
+    }
+}
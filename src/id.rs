@@ -67,6 +67,12 @@ impl Id {
     pub fn downcast_ref<T: 'static>(&self) -> Option<&T> {
         self.id.downcast_ref()
     }
+
+    /// Human readable text of the inner ID, used when no label is given.
+    pub(crate) fn label(&self) -> String {
+        let debug = self.debug;
+        format!("{:?}", debug(self.id.as_ref()))
+    }
 }
 
 impl<T: 'static> AsRef<T> for Id {
@@ -99,6 +105,12 @@ mod tests {
         assert_ne!(id1, id4);
     }
 
+    #[test]
+    fn label() {
+        assert_eq!(Id::new(42_usize).label(), "42");
+        assert_eq!(Id::new("in").label(), "\"in\"");
+    }
+
     #[test]
     fn hash() {
         let id1 = Id::new(42_usize);
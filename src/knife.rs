@@ -0,0 +1,153 @@
+use egui::{emath::TSTransform, Id, LayerId, Order, PointerButton, Pos2, Rect, Stroke};
+
+use crate::{cable::CableId, state::State};
+
+/// Tracks the knife stroke of a canvas and returns the cut cables when the stroke ends across any.
+///
+/// The stroke is on the screen, and the transform of a panned canvas maps cable paths onto the screen.
+pub(crate) fn knife(
+    ctx: &egui::Context,
+    canvas: Id,
+    rect: Rect,
+    transform: Option<TSTransform>,
+) -> Option<Vec<CableId>> {
+    let id = canvas.with("knife");
+    let mut start = ctx.data(|data| data.get_temp::<Pos2>(id));
+    let (pressed, released, origin, pos, command, escape) = ctx.input(|input| {
+        (
+            input.pointer.button_pressed(PointerButton::Secondary),
+            input.pointer.button_released(PointerButton::Secondary),
+            input.pointer.press_origin(),
+            input.pointer.latest_pos(),
+            input.modifiers.command,
+            input.key_pressed(egui::Key::Escape),
+        )
+    });
+
+    if pressed && command {
+        // a press on a widget, like a cable control with its context menu, is not a stroke
+        let background = canvas.with("background");
+        let hovered = ctx.interaction_snapshot(|interaction| interaction.hovered.clone());
+        let on_widget = hovered.into_iter().any(|id| {
+            id != background
+                && ctx
+                    .read_response(id)
+                    .is_some_and(|widget| widget.sense.click)
+        });
+        start = origin.filter(|origin| rect.contains(*origin) && !on_widget);
+    }
+    let mut cut = None;
+    if let (Some(from), Some(to)) = (start, pos) {
+        if released {
+            let inverse = transform.unwrap_or_default().inverse();
+            let (from, to) = (inverse * from, inverse * to);
+            let cable_ids: Vec<_> = State::get_with_ctx(ctx)
+                .cable_paths()
+                .into_iter()
+                .filter(|path| {
+                    path.canvas == Some(canvas) && path.shape.intersects_segment(from, to)
+                })
+                .map(|path| path.cable_id)
+                .collect();
+            cut = (!cable_ids.is_empty()).then_some(cable_ids);
+            start = None;
+        } else if escape {
+            start = None;
+        } else {
+            // preview
+            let painter = ctx.layer_painter(LayerId::new(Order::Foreground, id));
+            let color = ctx.style().visuals.error_fg_color;
+            painter.extend(egui::Shape::dashed_line(
+                &[from, to],
+                Stroke::new(1.5, color),
+                6.0,
+                4.0,
+            ));
+        }
+    }
+
+    ctx.data_mut(|data| match start {
+        Some(start) => data.insert_temp(id, start),
+        None => data.remove::<Pos2>(id),
+    });
+    cut
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, Modifiers};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    fn run(ctx: &egui::Context, events: Vec<egui::Event>) -> Option<Vec<CableId>> {
+        let canvas = CableCanvas::new("canvas").knife(true);
+        let response = run_canvas(ctx, canvas, Modifiers::COMMAND, events, |ui| {
+            put_ports(
+                ui,
+                [
+                    (0, pos2(100.0, 100.0)),
+                    (1, pos2(300.0, 100.0)),
+                    (2, pos2(100.0, 300.0)),
+                    (3, pos2(300.0, 300.0)),
+                ],
+            );
+            ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+            ui.add(Cable::new(1, Plug::to(2), Plug::to(3)));
+        });
+        response.cables_cut().map(|cable_ids| cable_ids.to_vec())
+    }
+
+    #[test]
+    fn cut_cables() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![]);
+        run(&ctx, vec![]);
+        let button = |pos, pressed| {
+            pointer_button(pos, PointerButton::Secondary, pressed, Modifiers::COMMAND)
+        };
+        assert_eq!(run(&ctx, vec![button(pos2(200.0, 50.0), true)]), None);
+        let to = pos2(200.0, 200.0);
+        assert_eq!(run(&ctx, vec![egui::Event::PointerMoved(to)]), None);
+        assert_eq!(
+            run(&ctx, vec![button(to, false)]),
+            Some(vec![egui::Id::new(0)])
+        );
+    }
+
+    #[test]
+    fn stroke_missing_cables() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![]);
+        run(&ctx, vec![]);
+        let button = |pos, pressed| {
+            pointer_button(pos, PointerButton::Secondary, pressed, Modifiers::COMMAND)
+        };
+        // between the cables
+        let (from, to) = (pos2(150.0, 200.0), pos2(250.0, 200.0));
+        run(
+            &ctx,
+            vec![egui::Event::PointerMoved(from), button(from, true)],
+        );
+        run(&ctx, vec![egui::Event::PointerMoved(to)]);
+        assert_eq!(run(&ctx, vec![button(to, false)]), None);
+    }
+
+    #[test]
+    fn no_stroke_from_a_cable_control() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![]);
+        run(&ctx, vec![]);
+        let button = |pos, pressed| {
+            pointer_button(pos, PointerButton::Secondary, pressed, Modifiers::COMMAND)
+        };
+        // the control of the lower cable shows up on hover, at the middle of the cable
+        let from = pos2(218.0, 320.0);
+        run(&ctx, vec![egui::Event::PointerMoved(from)]);
+        run(&ctx, vec![egui::Event::PointerMoved(from)]);
+        run(&ctx, vec![button(from, true)]);
+        let to = pos2(200.0, 50.0);
+        run(&ctx, vec![egui::Event::PointerMoved(to)]);
+        assert_eq!(run(&ctx, vec![button(to, false)]), None);
+    }
+}
@@ -0,0 +1,730 @@
+//! Automatic placement of nodes, for graphs imported without positions.
+//!
+//! A [`LayoutGraph`] is built by hand or from the nodes and cables shown on a canvas,
+//! and a layout turns it into positions without touching egui, so it runs headless.
+//! [`LayeredLayout`] places the nodes all at once, and [`ForceLayout`] moves them a step at a time.
+
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::TAU;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use egui::{vec2, Id, Pos2, Rect, Vec2};
+
+use crate::{
+    cable::CableId,
+    node::{is_node_dragged, move_nodes, node_pos, Axis, NodeId},
+    port::PortId,
+    state::State,
+};
+
+/// Nodes with their sizes and the cables between them.
+#[derive(Debug, Clone, Default)]
+pub struct LayoutGraph {
+    nodes: Vec<(NodeId, Vec2)>,
+    // cable, from, to
+    edges: Vec<(CableId, NodeId, NodeId)>,
+    positions: HashMap<NodeId, Pos2>,
+}
+
+/// Positions of the nodes of a [`LayoutGraph`].
+#[derive(Debug, Clone, Default)]
+pub struct Layout {
+    /// The top-left corners of the nodes, in the order they were added to the graph.
+    pub positions: Vec<(NodeId, Pos2)>,
+    /// Points for the cables spanning more than one layer to pass through, from the `from` node to the `to` node,
+    /// so they go around the nodes in between.
+    pub waypoints: Vec<(CableId, Vec<Pos2>)>,
+}
+
+/// Sugiyama-style layout placing the nodes in layers so that cables go one way, with few crossings.
+///
+/// Cycles are broken by reversing some cables, each node goes to the layer after its furthest predecessor,
+/// the nodes in each layer are ordered by the barycenters of their neighbors, and then they are
+/// moved toward their neighbors as far as the spacing allows. The same graph always gets the same layout.
+#[derive(Debug, Clone)]
+pub struct LayeredLayout {
+    direction: Axis,
+    layer_spacing: f32,
+    node_spacing: f32,
+    origin: Pos2,
+}
+
+/// Fruchterman–Reingold spring embedder for graphs without a direction.
+///
+/// Every node pushes the others away, and cables pull their nodes together, until they settle
+/// at about [`ForceLayout::ideal_length`] apart. It runs a [`ForceLayout::step`] at a time, usually one each frame,
+/// so the user can watch the graph untangle and drag nodes meanwhile. Pinned nodes push and pull the others
+/// but stay where they are. Each frame, call [`ForceLayout::pin_dragged`], then [`ForceLayout::step`] and
+/// [`ForceLayout::apply`] until it returns `false`.
+#[derive(Debug, Clone)]
+pub struct ForceLayout {
+    node_ids: Vec<NodeId>,
+    // the ideal distance between the centers of two nodes is the ideal length plus their radii
+    radii: Vec<f32>,
+    sizes: Vec<Vec2>,
+    centers: Vec<Pos2>,
+    edges: Vec<(usize, usize)>,
+    pinned: HashSet<usize>,
+    ideal_length: f32,
+    cooling: f32,
+    // the furthest a node moves in a step
+    temperature: f32,
+}
+
+impl LayoutGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The nodes shown recently on a canvas, connected by the cables between their ports.
+    ///
+    /// A cable goes from the node with the output port to the node with the input port.
+    pub fn from_canvas(ctx: &egui::Context, canvas_id_source: impl Hash) -> Self {
+        let canvas = Id::new(canvas_id_source);
+        let state = State::get_with_ctx(ctx);
+        let mut nodes: Vec<_> = state
+            .node_rects()
+            .into_iter()
+            .filter(|node| node.canvas == Some(canvas))
+            .collect();
+        // the state is unordered, so the nodes are ordered by their positions to be deterministic
+        nodes.sort_by(|a, b| {
+            let (a, b) = (a.rect.min, b.rect.min);
+            a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y))
+        });
+        let mut ports: HashMap<PortId, (usize, bool)> = HashMap::new();
+        for (i, node) in nodes.iter().enumerate() {
+            ports.extend(node.inputs.iter().map(|port| (port.clone(), (i, false))));
+            ports.extend(node.outputs.iter().map(|port| (port.clone(), (i, true))));
+        }
+        let mut edges: Vec<_> = state
+            .all_cable_ends()
+            .into_iter()
+            .filter_map(|ends| {
+                let a = ports.get(ends.in_port.as_ref()?)?;
+                let b = ports.get(ends.out_port.as_ref()?)?;
+                let (from, to) = if !a.1 && b.1 { (b.0, a.0) } else { (a.0, b.0) };
+                Some((ends.cable_id, from, to))
+            })
+            .collect();
+        edges.sort_by_key(|(_, from, to)| (*from, *to));
+
+        let mut graph = LayoutGraph::new();
+        for (cable_id, from, to) in edges {
+            graph
+                .edges
+                .push((cable_id, nodes[from].node_id, nodes[to].node_id));
+        }
+        for node in nodes {
+            graph = graph
+                .node(node.node_id, node.rect.size())
+                .position(node.node_id, node.rect.min);
+        }
+        graph
+    }
+
+    pub fn node(mut self, node_id: NodeId, size: impl Into<Vec2>) -> Self {
+        self.nodes.push((node_id, size.into()));
+        self
+    }
+
+    /// Sets the current top-left corner of a node, where a [`ForceLayout`] starts from.
+    pub fn position(mut self, node_id: NodeId, pos: impl Into<Pos2>) -> Self {
+        self.positions.insert(node_id, pos.into());
+        self
+    }
+
+    /// Adds a cable between two nodes. Cables to nodes not in the graph are left out of layouts.
+    pub fn edge<T: Debug + Eq + Hash + Send + Sync + 'static>(
+        mut self,
+        cable_id: T,
+        from: NodeId,
+        to: NodeId,
+    ) -> Self {
+        self.edges.push((CableId::new(cable_id), from, to));
+        self
+    }
+
+    /// The edges between different nodes in the graph, as indices of the nodes.
+    fn indexed_edges(&self) -> Vec<(CableId, usize, usize)> {
+        let index: HashMap<NodeId, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, (node_id, _))| (*node_id, i))
+            .collect();
+        self.edges
+            .iter()
+            .filter_map(|(cable_id, from, to)| {
+                let (from, to) = (*index.get(from)?, *index.get(to)?);
+                (from != to).then_some((*cable_id, from, to))
+            })
+            .collect()
+    }
+}
+
+impl Layout {
+    /// Moves the nodes when they are shown next, and returns the new top-left corners for the app to persist.
+    pub fn apply(&self, ctx: &egui::Context) -> Vec<(NodeId, Pos2)> {
+        move_nodes(ctx, self.positions.clone())
+    }
+}
+
+impl Default for LayeredLayout {
+    fn default() -> Self {
+        LayeredLayout {
+            direction: Axis::Horizontal,
+            layer_spacing: 80.0,
+            node_spacing: 30.0,
+            origin: Pos2::ZERO,
+        }
+    }
+}
+
+impl LayeredLayout {
+    /// The direction cables go in. Defaults to horizontal, from the outputs on the right of nodes to the inputs on the left.
+    pub fn direction(mut self, direction: Axis) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Space between layers.
+    pub fn layer_spacing(mut self, spacing: f32) -> Self {
+        self.layer_spacing = spacing;
+        self
+    }
+
+    /// Space between nodes in a layer.
+    pub fn node_spacing(mut self, spacing: f32) -> Self {
+        self.node_spacing = spacing;
+        self
+    }
+
+    /// The top-left corner of the whole layout.
+    pub fn origin(mut self, origin: impl Into<Pos2>) -> Self {
+        self.origin = origin.into();
+        self
+    }
+
+    pub fn run(&self, graph: &LayoutGraph) -> Layout {
+        let edges = graph.indexed_edges();
+        let n = graph.nodes.len();
+        let (main, cross) = match self.direction {
+            Axis::Horizontal => (0, 1),
+            Axis::Vertical => (1, 0),
+        };
+
+        let reversed = break_cycles(n, &edges);
+        let mut layer_of = assign_layers(n, &edges, &reversed);
+
+        // long edges go through dummy vertices, one in each layer they cross
+        let mut sizes: Vec<Vec2> = graph.nodes.iter().map(|(_, size)| *size).collect();
+        let mut chains = vec![];
+        let mut down: Vec<Vec<usize>> = vec![vec![]; n];
+        for (i, (_, from, to)) in edges.iter().enumerate() {
+            let (from, to) = if reversed[i] {
+                (*to, *from)
+            } else {
+                (*from, *to)
+            };
+            let mut chain = vec![from];
+            for layer in layer_of[from] + 1..layer_of[to] {
+                sizes.push(Vec2::ZERO);
+                layer_of.push(layer);
+                down.push(vec![]);
+                chain.push(sizes.len() - 1);
+            }
+            chain.push(to);
+            for pair in chain.windows(2) {
+                down[pair[0]].push(pair[1]);
+            }
+            chains.push(chain);
+        }
+        let mut up: Vec<Vec<usize>> = vec![vec![]; sizes.len()];
+        for (v, succs) in down.iter().enumerate() {
+            for w in succs {
+                up[*w].push(v);
+            }
+        }
+        let layer_count = layer_of.iter().max().map_or(0, |max| max + 1);
+        let mut layers: Vec<Vec<usize>> = vec![vec![]; layer_count];
+        for (v, layer) in layer_of.iter().enumerate() {
+            layers[*layer].push(v);
+        }
+
+        order_layers(&mut layers, &up, &down);
+
+        // the centers along the cross axis
+        let extent = |v: usize| sizes[v][cross];
+        let mut centers = vec![0.0; sizes.len()];
+        for layer in &layers {
+            let mut next = 0.0;
+            for v in layer {
+                centers[*v] = next + extent(*v) / 2.0;
+                next += extent(*v) + self.node_spacing;
+            }
+        }
+        for _ in 0..4 {
+            for layer in layers.iter().skip(1) {
+                self.place(layer, &up, &extent, &mut centers);
+            }
+            for layer in layers.iter().rev().skip(1) {
+                self.place(layer, &down, &extent, &mut centers);
+            }
+        }
+
+        // the layers along the main axis
+        let mut layer_centers = vec![];
+        let mut next = 0.0;
+        for layer in &layers {
+            let thickness = layer.iter().map(|v| sizes[*v][main]).fold(0.0, f32::max);
+            layer_centers.push(next + thickness / 2.0);
+            next += thickness + self.layer_spacing;
+        }
+        let min_cross = (0..sizes.len())
+            .map(|v| centers[v] - extent(v) / 2.0)
+            .fold(f32::INFINITY, f32::min);
+        let center = |v: usize| {
+            let mut center = [0.0; 2];
+            center[main] = layer_centers[layer_of[v]];
+            center[cross] = centers[v] - min_cross;
+            self.origin + vec2(center[0], center[1])
+        };
+
+        let positions = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(v, (node_id, size))| (*node_id, center(v) - *size / 2.0))
+            .collect();
+        let waypoints = edges
+            .into_iter()
+            .zip(chains)
+            .enumerate()
+            .filter(|(_, (_, chain))| chain.len() > 2)
+            .map(|(i, ((cable_id, _, _), chain))| {
+                let mut points: Vec<Pos2> = chain[1..chain.len() - 1]
+                    .iter()
+                    .map(|v| center(*v))
+                    .collect();
+                if reversed[i] {
+                    points.reverse();
+                }
+                (cable_id, points)
+            })
+            .collect();
+        Layout {
+            positions,
+            waypoints,
+        }
+    }
+
+    /// Moves the vertices of a layer as close to the average of their neighbors as the spacing allows, keeping their order.
+    fn place(
+        &self,
+        layer: &[usize],
+        neighbors: &[Vec<usize>],
+        extent: &impl Fn(usize) -> f32,
+        centers: &mut [f32],
+    ) {
+        // With the minimum offsets from the first vertex taken out, the rest must not decrease,
+        // which is an isotonic regression solved by pooling adjacent violators.
+        let mut offsets = vec![0.0];
+        for pair in layer.windows(2) {
+            let gap = (extent(pair[0]) + extent(pair[1])) / 2.0 + self.node_spacing;
+            offsets.push(offsets[offsets.len() - 1] + gap);
+        }
+        // (sum, count) of the targets in each block
+        let mut blocks: Vec<(f32, usize)> = vec![];
+        for (i, v) in layer.iter().enumerate() {
+            let target = if neighbors[*v].is_empty() {
+                centers[*v]
+            } else {
+                let sum: f32 = neighbors[*v].iter().map(|w| centers[*w]).sum();
+                sum / neighbors[*v].len() as f32
+            };
+            blocks.push((target - offsets[i], 1));
+            while blocks.len() > 1 {
+                let (sum, count) = blocks[blocks.len() - 1];
+                let (prev_sum, prev_count) = blocks[blocks.len() - 2];
+                if prev_sum / prev_count as f32 <= sum / count as f32 {
+                    break;
+                }
+                blocks.pop();
+                *blocks.last_mut().unwrap() = (prev_sum + sum, prev_count + count);
+            }
+        }
+        let mut i = 0;
+        for (sum, count) in blocks {
+            for _ in 0..count {
+                centers[layer[i]] = sum / count as f32 + offsets[i];
+                i += 1;
+            }
+        }
+    }
+}
+
+impl ForceLayout {
+    /// Starts from the positions set on the graph. The nodes without one are put on a circle around the others.
+    pub fn new(graph: &LayoutGraph) -> Self {
+        let ideal_length = 100.0;
+        let n = graph.nodes.len();
+        let center = |(node_id, size): &(NodeId, Vec2)| {
+            graph.positions.get(node_id).map(|pos| *pos + *size / 2.0)
+        };
+        let known: Vec<Pos2> = graph.nodes.iter().filter_map(center).collect();
+        let middle = Rect::from_points(&known).center();
+        let middle = if known.is_empty() { Pos2::ZERO } else { middle };
+        let radius = ideal_length * n as f32 / TAU;
+        let centers = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                center(node)
+                    .unwrap_or_else(|| middle + radius * Vec2::angled(TAU * i as f32 / n as f32))
+            })
+            .collect();
+        ForceLayout {
+            node_ids: graph.nodes.iter().map(|(node_id, _)| *node_id).collect(),
+            radii: graph
+                .nodes
+                .iter()
+                .map(|(_, size)| size.length() / 2.0)
+                .collect(),
+            sizes: graph.nodes.iter().map(|(_, size)| *size).collect(),
+            centers,
+            edges: graph
+                .indexed_edges()
+                .into_iter()
+                .map(|(_, from, to)| (from, to))
+                .collect(),
+            pinned: HashSet::new(),
+            ideal_length,
+            cooling: 0.95,
+            temperature: ideal_length,
+        }
+    }
+
+    /// The length of cables between two nodes at rest, not counting the nodes themselves. Defaults to 100.
+    pub fn ideal_length(mut self, length: f32) -> Self {
+        self.ideal_length = length;
+        self.temperature = length;
+        self
+    }
+
+    /// How much of the speed of the nodes is kept after each step, below 1. Defaults to 0.95.
+    pub fn cooling(mut self, cooling: f32) -> Self {
+        self.cooling = cooling;
+        self
+    }
+
+    /// Keeps a node at the top-left corner until it is unpinned, and lets the others adjust to it.
+    pub fn pin(&mut self, node_id: NodeId, pos: Pos2) {
+        if let Some(i) = self.node_ids.iter().position(|id| *id == node_id) {
+            self.centers[i] = pos + self.sizes[i] / 2.0;
+            self.pinned.insert(i);
+            self.temperature = self.temperature.max(self.ideal_length / 4.0);
+        }
+    }
+
+    pub fn unpin(&mut self, node_id: NodeId) {
+        if let Some(i) = self.node_ids.iter().position(|id| *id == node_id) {
+            self.pinned.remove(&i);
+        }
+    }
+
+    /// Pins the nodes the user is dragging, where they are now. They stay pinned after they are dropped.
+    pub fn pin_dragged(&mut self, ctx: &egui::Context) {
+        for node_id in self.node_ids.clone() {
+            if is_node_dragged(ctx, node_id) {
+                if let Some(pos) = node_pos(ctx, node_id) {
+                    self.pin(node_id, pos);
+                }
+            }
+        }
+    }
+
+    pub fn is_settled(&self) -> bool {
+        self.temperature < 0.5
+    }
+
+    /// Moves the nodes along the forces on them, each by the temperature at most, and cools down.
+    /// Returns `false` without moving them once they have settled.
+    pub fn step(&mut self) -> bool {
+        if self.is_settled() {
+            return false;
+        }
+        let n = self.centers.len();
+        let ideal = |u: usize, v: usize| self.ideal_length + self.radii[u] + self.radii[v];
+        let mut forces = vec![Vec2::ZERO; n];
+        for u in 0..n {
+            for v in u + 1..n {
+                let delta = self.centers[u] - self.centers[v];
+                // like the grid variant of the original algorithm, so unconnected nodes don't drift away
+                if delta.length() > 2.0 * ideal(u, v) {
+                    continue;
+                }
+                // nodes on top of each other are pushed apart in a direction given by their order
+                let direction = if delta == Vec2::ZERO {
+                    Vec2::angled(TAU * u as f32 / n as f32)
+                } else {
+                    delta.normalized()
+                };
+                let force = direction * ideal(u, v).powi(2) / delta.length().max(1.0);
+                forces[u] += force;
+                forces[v] -= force;
+            }
+        }
+        for (from, to) in &self.edges {
+            let delta = self.centers[*to] - self.centers[*from];
+            let force = delta * delta.length() / ideal(*from, *to);
+            forces[*from] += force;
+            forces[*to] -= force;
+        }
+        for (u, force) in forces.into_iter().enumerate() {
+            if !self.pinned.contains(&u) && force != Vec2::ZERO {
+                let length = force.length();
+                self.centers[u] += force / length * length.min(self.temperature);
+            }
+        }
+        self.temperature *= self.cooling;
+        true
+    }
+
+    /// The top-left corners of the nodes, in the order they were added to the graph.
+    pub fn positions(&self) -> Vec<(NodeId, Pos2)> {
+        (0..self.node_ids.len())
+            .map(|i| (self.node_ids[i], self.centers[i] - self.sizes[i] / 2.0))
+            .collect()
+    }
+
+    /// Moves the nodes other than the pinned ones when they are shown next,
+    /// and returns the new top-left corners for the app to persist.
+    pub fn apply(&self, ctx: &egui::Context) -> Vec<(NodeId, Pos2)> {
+        let moves = self
+            .positions()
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !self.pinned.contains(i))
+            .map(|(_, moved)| moved)
+            .collect();
+        move_nodes(ctx, moves)
+    }
+}
+
+/// Returns which edges to reverse to make the graph acyclic: the ones going back to a node on the depth-first search stack.
+fn break_cycles(n: usize, edges: &[(CableId, usize, usize)]) -> Vec<bool> {
+    let mut outgoing: Vec<Vec<usize>> = vec![vec![]; n];
+    for (i, (_, from, _)) in edges.iter().enumerate() {
+        outgoing[*from].push(i);
+    }
+    // 0: not visited, 1: on the stack, 2: done
+    let mut visit = vec![0u8; n];
+    let mut reversed = vec![false; edges.len()];
+    for root in 0..n {
+        if visit[root] != 0 {
+            continue;
+        }
+        visit[root] = 1;
+        let mut stack = vec![(root, 0)];
+        while let Some((v, next)) = stack.last_mut() {
+            let v = *v;
+            if let Some(edge) = outgoing[v].get(*next) {
+                *next += 1;
+                let to = edges[*edge].2;
+                match visit[to] {
+                    0 => {
+                        visit[to] = 1;
+                        stack.push((to, 0));
+                    }
+                    1 => reversed[*edge] = true,
+                    _ => {}
+                }
+            } else {
+                visit[v] = 2;
+                stack.pop();
+            }
+        }
+    }
+    reversed
+}
+
+/// Puts each node in the layer after its furthest predecessor, so the sources are in the first layer.
+fn assign_layers(n: usize, edges: &[(CableId, usize, usize)], reversed: &[bool]) -> Vec<usize> {
+    let mut outgoing: Vec<Vec<usize>> = vec![vec![]; n];
+    let mut incoming = vec![0; n];
+    for (i, (_, from, to)) in edges.iter().enumerate() {
+        let (from, to) = if reversed[i] {
+            (*to, *from)
+        } else {
+            (*from, *to)
+        };
+        outgoing[from].push(to);
+        incoming[to] += 1;
+    }
+    let mut layers = vec![0; n];
+    let mut ready: Vec<usize> = (0..n).rev().filter(|v| incoming[*v] == 0).collect();
+    while let Some(v) = ready.pop() {
+        for to in &outgoing[v] {
+            layers[*to] = layers[*to].max(layers[v] + 1);
+            incoming[*to] -= 1;
+            if incoming[*to] == 0 {
+                ready.push(*to);
+            }
+        }
+    }
+    layers
+}
+
+/// Orders the vertices in each layer by the barycenters of their neighbors, sweeping down and up,
+/// and keeps the order with the fewest crossings.
+fn order_layers(layers: &mut [Vec<usize>], up: &[Vec<usize>], down: &[Vec<usize>]) {
+    let mut best = layers.to_vec();
+    let mut fewest = crossings(layers, down);
+    for sweep in 0..24 {
+        if fewest == 0 {
+            break;
+        }
+        let (range, neighbors): (Vec<usize>, _) = if sweep % 2 == 0 {
+            ((1..layers.len()).collect(), up)
+        } else {
+            ((0..layers.len().saturating_sub(1)).rev().collect(), down)
+        };
+        for i in range {
+            let mut position = vec![0.0; up.len()];
+            for layer in layers.iter() {
+                for (p, v) in layer.iter().enumerate() {
+                    position[*v] = p as f32;
+                }
+            }
+            let barycenter = |v: usize| {
+                if neighbors[v].is_empty() {
+                    position[v]
+                } else {
+                    neighbors[v].iter().map(|w| position[*w]).sum::<f32>()
+                        / neighbors[v].len() as f32
+                }
+            };
+            // stable, so ties keep their order
+            layers[i].sort_by(|a, b| barycenter(*a).total_cmp(&barycenter(*b)));
+        }
+        let count = crossings(layers, down);
+        if count < fewest {
+            fewest = count;
+            best = layers.to_vec();
+        }
+    }
+    layers.clone_from_slice(&best);
+}
+
+fn crossings(layers: &[Vec<usize>], down: &[Vec<usize>]) -> usize {
+    let mut position = vec![0; down.len()];
+    for layer in layers {
+        for (p, v) in layer.iter().enumerate() {
+            position[*v] = p;
+        }
+    }
+    let mut count = 0;
+    for pair in layers.windows(2) {
+        let lines: Vec<(usize, usize)> = pair[0]
+            .iter()
+            .flat_map(|v| down[*v].iter().map(|w| (position[*v], position[*w])))
+            .collect();
+        for (i, a) in lines.iter().enumerate() {
+            count += lines[i + 1..]
+                .iter()
+                .filter(|b| (a.0 < b.0 && a.1 > b.1) || (a.0 > b.0 && a.1 < b.1))
+                .count();
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(i: usize) -> NodeId {
+        Id::new(i)
+    }
+
+    #[test]
+    fn layers_without_crossings() {
+        // 0 -> 2, 1 -> 3 and 0 -> 3, with the nodes of the second layer added in the crossing order,
+        // 3 -> 4 and a cycle back from 4 to 0
+        let mut graph = LayoutGraph::new();
+        for i in [0, 1, 3, 2, 4] {
+            graph = graph.node(node(i), [100.0, 50.0]);
+        }
+        let graph = graph
+            .edge(0, node(0), node(2))
+            .edge(1, node(1), node(3))
+            .edge(2, node(0), node(3))
+            .edge(3, node(3), node(4))
+            .edge(4, node(4), node(0));
+        let layout = LayeredLayout::default().run(&graph);
+        let pos = |i: usize| {
+            layout
+                .positions
+                .iter()
+                .find(|(node_id, _)| *node_id == node(i))
+                .unwrap()
+                .1
+        };
+
+        // the cable back to 0 is reversed, so 0 and 1 are the sources
+        assert_eq!(pos(0).x, 0.0);
+        assert_eq!(pos(1).x, 0.0);
+        assert_eq!(pos(2).x, 180.0);
+        assert_eq!(pos(3).x, 180.0);
+        assert_eq!(pos(4).x, 360.0);
+        // 0 -> 2 and 1 -> 3 don't cross
+        assert!((pos(0).y < pos(1).y) == (pos(2).y < pos(3).y));
+        // the nodes in a layer don't overlap
+        assert!((pos(0).y - pos(1).y).abs() >= 80.0);
+        assert!((pos(2).y - pos(3).y).abs() >= 80.0);
+        // the cable from 4 back to 0 passes through the layer in between
+        assert_eq!(layout.waypoints.len(), 1);
+        let (cable_id, points) = &layout.waypoints[0];
+        assert_eq!(*cable_id, CableId::new(4));
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].x, 230.0);
+        // deterministic
+        assert_eq!(
+            format!("{:?}", layout.positions),
+            format!("{:?}", LayeredLayout::default().run(&graph).positions)
+        );
+    }
+
+    #[test]
+    fn force_layout_settles() {
+        // a chain 0 - 1 - 2 and a node 3 without cables, all on top of each other, and 2 pinned
+        let mut graph = LayoutGraph::new();
+        for i in 0..4 {
+            graph = graph
+                .node(node(i), [40.0, 30.0])
+                .position(node(i), [0.0, 0.0]);
+        }
+        let graph = graph.edge(0, node(0), node(1)).edge(1, node(1), node(2));
+        let mut layout = ForceLayout::new(&graph);
+        layout.pin(node(2), Pos2::ZERO);
+        let mut steps = 0;
+        while layout.step() {
+            steps += 1;
+        }
+        let positions = layout.positions();
+        let distance = |a: usize, b: usize| positions[a].1.distance(positions[b].1);
+        assert!(steps < 200);
+        assert!(layout.is_settled());
+        assert_eq!(positions[2].1, Pos2::ZERO);
+        // the cables are about the ideal length plus the radii of the nodes
+        assert!((distance(0, 1) - 150.0).abs() < 5.0);
+        assert!((distance(1, 2) - 150.0).abs() < 5.0);
+        // the node without cables is pushed out of reach
+        for i in 0..3 {
+            assert!(distance(i, 3) >= 300.0);
+        }
+    }
+}
@@ -1,30 +1,54 @@
+mod accessibility;
+mod auto_pan;
 pub mod cable;
 pub mod cable_control;
 pub mod cable_params;
+pub mod canvas;
+mod context_menu;
 mod custom_widget;
 pub mod default_cable;
 pub mod default_plug;
 pub mod default_port;
 pub mod event;
+pub mod grid;
+pub mod group;
 pub mod id;
+mod knife;
+#[cfg(feature = "layout")]
+pub mod layout;
+pub mod minimap;
+pub mod node;
+mod path;
 pub mod plug;
 pub mod plug_params;
 pub mod port;
 pub mod port_params;
+pub mod port_scope;
 pub mod response;
+pub mod selection;
 mod state;
+#[cfg(test)]
+mod test_utils;
+pub mod touch;
 mod utils;
 
 pub mod prelude {
     pub use crate::cable::Cable;
     pub use crate::cable_params::CableParams;
+    pub use crate::canvas::{CableCanvas, CableClip, CableLayering, ConnectionMode, PlugFan};
     pub use crate::default_cable::DefaultControl;
     pub use crate::default_plug::DefaultPlug;
     pub use crate::default_port::DefaultPort;
     pub use crate::event::Event;
+    pub use crate::grid::SnapGrid;
+    pub use crate::group::Group;
+    pub use crate::minimap::Minimap;
+    pub use crate::node::Node;
     pub use crate::plug::Plug;
     pub use crate::plug_params::PlugParams;
     pub use crate::port::{Port, PortId};
     pub use crate::port_params::PortParams;
+    pub use crate::port_scope::PortScope;
     pub use crate::response::ResponseExt as _;
+    pub use crate::touch::PointerId;
 }
@@ -0,0 +1,199 @@
+use std::hash::Hash;
+
+use egui::{emath::TSTransform, vec2, Id, Rect, Response, Sense, Shape, Stroke, Ui, Vec2, Widget};
+
+use crate::{
+    canvas::{set_transform, Viewport},
+    selection::is_cable_selected_on,
+    state::State,
+};
+
+// space around the contents on the map
+const MARGIN: f32 = 20.0;
+
+/// An overview of the nodes, ports and cables of a canvas, with the rect of the canvas on the screen.
+///
+/// Clicking or dragging on it moves the view of a canvas with [`CableCanvas::pan_zoom`](crate::canvas::CableCanvas::pan_zoom)
+/// to center the point.
+#[derive(Debug, Clone)]
+pub struct Minimap {
+    canvas: Id,
+    size: Vec2,
+}
+
+impl Minimap {
+    /// Takes the ID source given to [`CableCanvas::new`](crate::canvas::CableCanvas::new).
+    pub fn new(canvas_id_source: impl Hash) -> Self {
+        Minimap {
+            canvas: Id::new(canvas_id_source),
+            size: vec2(200.0, 150.0),
+        }
+    }
+
+    pub fn size(mut self, size: Vec2) -> Self {
+        self.size = size;
+        self
+    }
+}
+
+impl Widget for Minimap {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let (rect, response) = ui.allocate_exact_size(self.size, Sense::click_and_drag());
+        let state = State::get_with_ctx(ui.ctx());
+        let ports: Vec<Rect> = state
+            .port_anchors()
+            .into_iter()
+            .filter(|anchor| anchor.canvas == Some(self.canvas))
+            .map(|anchor| anchor.rect)
+            .collect();
+        let nodes: Vec<Rect> = state
+            .node_rects()
+            .into_iter()
+            .filter(|node| node.canvas == Some(self.canvas))
+            .map(|node| node.rect)
+            .collect();
+        let paths: Vec<_> = state
+            .cable_paths()
+            .into_iter()
+            .filter(|path| path.canvas == Some(self.canvas))
+            .collect();
+        let viewport = Viewport::get(ui.ctx(), self.canvas);
+
+        // The contents in their own coordinates. The viewport is left out so the map stays still while jumping.
+        let mut bounds = Rect::NOTHING;
+        for rect in ports.iter().chain(&nodes) {
+            bounds = bounds.union(*rect);
+        }
+        for path in &paths {
+            for point in path.shape.polyline() {
+                bounds.extend_with(point);
+            }
+        }
+        if !bounds.is_positive() {
+            match &viewport {
+                Some(viewport) => bounds = viewport.visible(),
+                None => return response,
+            }
+        }
+        let bounds = bounds.expand(MARGIN);
+        // fit the bounds in the minimap keeping the aspect ratio
+        let scaling = (rect.width() / bounds.width()).min(rect.height() / bounds.height());
+        let to_map = TSTransform::new(
+            rect.center().to_vec2() - bounds.center().to_vec2() * scaling,
+            scaling,
+        );
+
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.visuals();
+            let painter = ui.painter_at(rect);
+            painter.rect(
+                rect,
+                2.0,
+                visuals.extreme_bg_color,
+                visuals.widgets.noninteractive.bg_stroke,
+            );
+            for node in &nodes {
+                painter.rect(
+                    to_map * *node,
+                    1.0,
+                    visuals.faint_bg_color,
+                    Stroke::new(1.0, visuals.weak_text_color()),
+                );
+            }
+            for path in &paths {
+                let stroke = if is_cable_selected_on(ui.ctx(), self.canvas, path.cable_id) {
+                    visuals.selection.stroke
+                } else {
+                    Stroke::new(1.0, visuals.weak_text_color())
+                };
+                let points = path.shape.polyline().into_iter().map(|p| to_map * p);
+                painter.add(Shape::line(points.collect(), stroke));
+            }
+            for port in &ports {
+                let port = to_map * *port;
+                painter.rect_filled(
+                    Rect::from_center_size(port.center(), port.size().max(Vec2::splat(2.0))),
+                    0.0,
+                    visuals.text_color(),
+                );
+            }
+            if let Some(viewport) = &viewport {
+                painter.rect_stroke(
+                    to_map * viewport.visible(),
+                    0.0,
+                    visuals.widgets.active.fg_stroke,
+                );
+            }
+        }
+
+        // jump to the point
+        if let (Some(pointer), Some(viewport)) = (response.interact_pointer_pos(), viewport) {
+            if let Some(transform) = viewport.transform {
+                let target = to_map.inverse() * pointer;
+                let translation =
+                    viewport.rect.center().to_vec2() - target.to_vec2() * transform.scaling;
+                set_transform(
+                    ui.ctx(),
+                    self.canvas,
+                    TSTransform::new(translation, transform.scaling),
+                );
+            }
+        }
+
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, Event};
+
+    use super::*;
+    use crate::{canvas::set_canvas_transform, prelude::*, test_utils::*};
+
+    /// Renders a minimap in a bottom panel and a panned canvas with two ports,
+    /// and returns the rect of the minimap.
+    fn run(ctx: &egui::Context, events: Vec<Event>) -> Rect {
+        let input = egui::RawInput {
+            events,
+            ..Default::default()
+        };
+        let mut rect = Rect::NOTHING;
+        // the bottom panel has to be shown before the central panel
+        let _ = ctx.run(input, |ctx| {
+            egui::TopBottomPanel::bottom("minimap").show(ctx, |ui| {
+                rect = ui.add(Minimap::new("canvas")).rect;
+            });
+            egui::CentralPanel::default().show(ctx, |ui| {
+                CableCanvas::new("canvas")
+                    .pan_zoom(true)
+                    .show(ui, two_ports);
+            });
+        });
+        rect
+    }
+
+    #[test]
+    fn click_to_center_the_point() {
+        let ctx = egui::Context::default();
+        set_canvas_transform(
+            &ctx,
+            "canvas",
+            TSTransform::from_translation(vec2(-1000.0, 0.0)),
+        );
+        run(&ctx, vec![]);
+        let minimap = run(&ctx, vec![]);
+        let canvas = Id::new("canvas");
+        let viewport = Viewport::get(&ctx, canvas).unwrap();
+        let bounds = Rect::from_min_max(pos2(100.0, 100.0), pos2(316.0, 116.0));
+
+        // the center of the minimap is the center of the ports
+        let center = minimap.center();
+        run(&ctx, vec![Event::PointerMoved(center)]);
+        run(&ctx, vec![button(center, true)]);
+        run(&ctx, vec![button(center, false)]);
+        let transform = ctx.data(|data| data.get_temp::<TSTransform>(canvas.with("transform")));
+        let centered = transform.unwrap().inverse() * viewport.rect.center();
+        assert!(centered.distance(bounds.center()) < 1.0);
+    }
+}
@@ -0,0 +1,618 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use egui::{vec2, Align2, Id, InnerResponse, Pos2, Rect, Ui, Vec2, WidgetText};
+
+use crate::{
+    canvas::{CanvasSettings, Viewport},
+    grid::SnapGrid,
+    port::{Port, PortId},
+    port_scope::{register_node, stand_in, StandIn},
+    selection::{extends_selection, is_node_selected_on, select_nodes_on, selected_nodes_on},
+    state::State,
+};
+
+pub type NodeId = Id;
+
+/// A box with a title bar, input ports on the left, the body in the middle and output ports on the right.
+///
+/// It is an [`egui::Window`], so it can be dragged by the title bar, collapsed and resized.
+/// The ports are registered with the window, so cables are [layered](crate::canvas::CableLayering) with it as well.
+/// Cables to the ports of a collapsed node end at the sides of the title bar.
+/// On a canvas with [`CableCanvas::pan_zoom`](crate::canvas::CableCanvas::pan_zoom) it moves with the contents,
+/// and on other canvases it is kept inside the canvas.
+///
+/// Clicking a node selects it, and shift-click adds it to the selection like cables, see [`crate::selection`].
+/// Dragging one of the selected nodes on a canvas moves all of them as far in the same frame, with their plugs and cables.
+/// [`Event::NodesMoved`](crate::event::Event::NodesMoved) tells the app when the nodes are dropped.
+///
+/// The rect of the node is an obstacle for the cables on the same canvas, which bend around it when they cross it.
+pub struct Node {
+    id: Id,
+    title: WidgetText,
+    inputs: Vec<(Port, WidgetText)>,
+    outputs: Vec<(Port, WidgetText)>,
+    default_pos: Option<Pos2>,
+    resizable: bool,
+    collapsible: bool,
+}
+
+/// The rect and the ports of a node shown recently, for the minimap, box selection, groups, layouts
+/// and the cables bending around it.
+#[derive(Debug, Clone)]
+pub(crate) struct NodeRect {
+    pub node_id: NodeId,
+    pub rect: Rect,
+    pub canvas: Option<Id>,
+    #[cfg(feature = "layout")]
+    pub inputs: Vec<PortId>,
+    #[cfg(feature = "layout")]
+    pub outputs: Vec<PortId>,
+}
+
+/// How far the selected nodes move together in this frame, in the coordinates of the canvas contents.
+#[derive(Debug, Clone, Copy)]
+struct GroupDrag {
+    // the node dragged by its title bar
+    node_id: NodeId,
+    // the drag delta egui adds to the dragged node by itself
+    pointer_delta: Vec2,
+    delta: Vec2,
+}
+
+// A newtype to give the positions set by `align_nodes` and `distribute_nodes` their own slot in the context data.
+#[derive(Debug, Clone, Default)]
+struct PendingMoves(HashMap<NodeId, Pos2>);
+
+/// Which edges or centers [`align_nodes`] lines up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeAlign {
+    Left,
+    HorizontalCenter,
+    Right,
+    Top,
+    VerticalCenter,
+    Bottom,
+}
+
+/// The direction [`distribute_nodes`] spaces nodes along.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+impl Node {
+    pub fn new(id_source: impl Hash, title: impl Into<WidgetText>) -> Self {
+        Node {
+            id: Id::new(id_source),
+            title: title.into(),
+            inputs: vec![],
+            outputs: vec![],
+            default_pos: None,
+            resizable: true,
+            collapsible: true,
+        }
+    }
+
+    /// Adds a port with a label to the left column.
+    /// Plugs and lines are anchored at the left edge of the port unless it has its own anchors.
+    pub fn input(mut self, port: Port, label: impl Into<WidgetText>) -> Self {
+        self.inputs.push((port, label.into()));
+        self
+    }
+
+    /// Adds a port with a label to the right column.
+    /// Plugs and lines are anchored at the right edge of the port unless it has its own anchors.
+    pub fn output(mut self, port: Port, label: impl Into<WidgetText>) -> Self {
+        self.outputs.push((port, label.into()));
+        self
+    }
+
+    /// Sets the position of the top-left corner the first time the node is shown.
+    pub fn default_pos(mut self, pos: impl Into<Pos2>) -> Self {
+        self.default_pos = Some(pos.into());
+        self
+    }
+
+    /// Nodes can't be resized on a canvas with pan and zoom.
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn collapsible(mut self, collapsible: bool) -> Self {
+        self.collapsible = collapsible;
+        self
+    }
+
+    /// Shows the node like [`egui::Window::show`]. The inner value is `None` while the node is collapsed.
+    pub fn show<R>(
+        self,
+        ctx: &egui::Context,
+        add_contents: impl FnOnce(&mut Ui) -> R,
+    ) -> Option<InnerResponse<Option<R>>> {
+        let settings = CanvasSettings::current(ctx);
+        let port_ids = |ports: &[(Port, WidgetText)]| -> Vec<PortId> {
+            ports.iter().map(|(port, _)| port.id().clone()).collect()
+        };
+        let (input_ids, output_ids) = (port_ids(&self.inputs), port_ids(&self.outputs));
+
+        let canvas = settings.id.unwrap_or(Id::NULL);
+        let selected = is_node_selected_on(ctx, canvas, self.id);
+        let mut window = egui::Window::new(self.title)
+            .id(self.id)
+            // egui resizes windows to the pointer on the screen, which is off on a panned canvas
+            .resizable(self.resizable && settings.transform.is_none())
+            .collapsible(self.collapsible);
+        if let Some(pos) = self.default_pos {
+            window = window.default_pos(pos);
+        }
+        let current_pos = node_pos(ctx, self.id);
+        if let Some(pos) = take_pending_move(ctx, self.id) {
+            window = window.current_pos(pos);
+        } else if let (Some(drag), Some(pos)) = (
+            selected.then(|| group_drag(ctx, canvas)).flatten(),
+            current_pos,
+        ) {
+            let delta = if drag.node_id == self.id {
+                drag.delta - drag.pointer_delta
+            } else {
+                drag.delta
+            };
+            window = window.current_pos(pos + delta);
+        }
+        if selected {
+            let style = ctx.style();
+            window =
+                window.frame(egui::Frame::window(&style).stroke(style.visuals.selection.stroke));
+        }
+        let viewport = settings.id.and_then(|canvas| Viewport::get(ctx, canvas));
+        window = match (settings.transform, viewport) {
+            // the position is in the coordinates of the contents
+            (Some(_), _) => window.constrain(false),
+            (None, Some(viewport)) => window.constrain_to(viewport.rect),
+            (None, None) => window,
+        };
+        let (inputs, outputs) = (self.inputs, self.outputs);
+        let shown = window.show(ctx, |ui| {
+            ui.horizontal_top(|ui| {
+                egui::Grid::new("inputs").show(ui, |ui| {
+                    for (port, label) in inputs {
+                        ui.add(port.default_anchor(Align2::LEFT_CENTER));
+                        ui.label(label);
+                        ui.end_row();
+                    }
+                });
+                let inner = ui.vertical(add_contents).inner;
+                egui::Grid::new("outputs").show(ui, |ui| {
+                    for (port, label) in outputs {
+                        ui.label(label);
+                        ui.add(port.default_anchor(Align2::RIGHT_CENTER));
+                        ui.end_row();
+                    }
+                });
+                inner
+            })
+            .inner
+        })?;
+
+        register_node(ctx, self.id);
+        let layer = shown.response.layer_id;
+        if let Some(transform) = settings.transform {
+            ctx.set_transform_layer(layer, transform);
+        }
+
+        // selection, before the drag starts so the node is dragged with the others
+        let (pressed, pointer, modifiers) = ctx.input(|input| {
+            let pointer = &input.pointer;
+            (
+                pointer.primary_pressed(),
+                pointer.interact_pos(),
+                input.modifiers,
+            )
+        });
+        if pressed && pointer.is_some_and(|pointer| ctx.layer_id_at(pointer) == Some(layer)) {
+            let mut node_ids = selected_nodes_on(ctx, canvas);
+            if extends_selection(modifiers, settings.cable_deletion) {
+                if selected {
+                    node_ids.retain(|node_id| *node_id != self.id);
+                } else {
+                    node_ids.push(self.id);
+                }
+                select_nodes_on(ctx, canvas, node_ids);
+            } else if !selected {
+                select_nodes_on(ctx, canvas, [self.id]);
+            }
+        }
+        if let Some(canvas) = settings.id.filter(|_| shown.response.drag_stopped()) {
+            let moved = if selected {
+                selected_nodes_on(ctx, canvas)
+            } else {
+                vec![self.id]
+            };
+            if let Some(grid) = SnapGrid::current(ctx) {
+                let snapped = moved
+                    .iter()
+                    .filter_map(|node_id| Some((*node_id, grid.snap(node_pos(ctx, *node_id)?))))
+                    .collect();
+                move_nodes(ctx, snapped);
+            }
+            ctx.data_mut(|data| data.insert_temp(canvas.with("nodes-moved"), moved));
+        }
+
+        let rect = shown.response.rect;
+        let mut state = State::clone(&State::get_with_ctx(ctx));
+        state.update_node_rect(
+            self.id,
+            NodeRect {
+                node_id: self.id,
+                rect,
+                canvas: settings.id,
+                #[cfg(feature = "layout")]
+                inputs: input_ids.clone(),
+                #[cfg(feature = "layout")]
+                outputs: output_ids.clone(),
+            },
+        );
+        if shown.inner.is_none() {
+            // collapsed to the title bar
+            let view = settings.clip_rect.unwrap_or(Rect::EVERYTHING);
+            for (port_ids, side) in [
+                (input_ids, Align2::LEFT_CENTER),
+                (output_ids, Align2::RIGHT_CENTER),
+            ] {
+                for port_id in &port_ids {
+                    let anchor = side.into();
+                    stand_in(
+                        &mut state,
+                        ctx,
+                        port_id,
+                        StandIn {
+                            rect,
+                            view,
+                            anchor,
+                            layer,
+                        },
+                    );
+                }
+            }
+        }
+        state.store_to_ctx(ctx);
+        Some(shown)
+    }
+}
+
+/// Returns the top-left corner of a node shown recently, in the coordinates of the canvas contents.
+pub fn node_pos(ctx: &egui::Context, node_id: NodeId) -> Option<Pos2> {
+    ctx.memory(|memory| memory.area_rect(node_id))
+        .map(|rect| rect.min)
+}
+
+/// Lines up the nodes by their edges or centers, to the outermost edge or the center of all of them.
+///
+/// The nodes move when they are shown next, and the new top-left corners are returned for the app to persist.
+pub fn align_nodes(
+    ctx: &egui::Context,
+    node_ids: &[NodeId],
+    align: NodeAlign,
+) -> Vec<(NodeId, Pos2)> {
+    let rects = node_rects(ctx, node_ids);
+    let bounds = rects
+        .iter()
+        .fold(Rect::NOTHING, |bounds, (_, rect)| bounds.union(*rect));
+    let moves = rects
+        .into_iter()
+        .map(|(node_id, rect)| {
+            let delta = match align {
+                NodeAlign::Left => vec2(bounds.left() - rect.left(), 0.0),
+                NodeAlign::HorizontalCenter => vec2(bounds.center().x - rect.center().x, 0.0),
+                NodeAlign::Right => vec2(bounds.right() - rect.right(), 0.0),
+                NodeAlign::Top => vec2(0.0, bounds.top() - rect.top()),
+                NodeAlign::VerticalCenter => vec2(0.0, bounds.center().y - rect.center().y),
+                NodeAlign::Bottom => vec2(0.0, bounds.bottom() - rect.bottom()),
+            };
+            (node_id, rect.min + delta)
+        })
+        .collect();
+    move_nodes(ctx, moves)
+}
+
+/// Spaces the nodes evenly along the axis, keeping the first and the last ones in place.
+///
+/// The nodes move when they are shown next, and the new top-left corners are returned for the app to persist.
+pub fn distribute_nodes(
+    ctx: &egui::Context,
+    node_ids: &[NodeId],
+    axis: Axis,
+) -> Vec<(NodeId, Pos2)> {
+    let d = match axis {
+        Axis::Horizontal => 0,
+        Axis::Vertical => 1,
+    };
+    let mut rects = node_rects(ctx, node_ids);
+    if rects.len() < 3 {
+        return vec![];
+    }
+    rects.sort_by(|(_, a), (_, b)| a.center()[d].total_cmp(&b.center()[d]));
+    let (first, last) = (rects[0].1, rects[rects.len() - 1].1);
+    let sizes: f32 = rects.iter().map(|(_, rect)| rect.size()[d]).sum();
+    let gap = (last.max[d] - first.min[d] - sizes) / (rects.len() - 1) as f32;
+    let mut next = first.min[d];
+    let moves = rects
+        .into_iter()
+        .map(|(node_id, rect)| {
+            let mut pos = rect.min;
+            pos[d] = next;
+            next += rect.size()[d] + gap;
+            (node_id, pos)
+        })
+        .collect();
+    move_nodes(ctx, moves)
+}
+
+/// Returns whether the user is dragging the node by its title bar, or another selected node along with it.
+pub fn is_node_dragged(ctx: &egui::Context, node_id: NodeId) -> bool {
+    let Some(dragged) = ctx.dragged_id() else {
+        return false;
+    };
+    // egui moves a window by a handle derived from its ID
+    let handle = |node_id: NodeId| node_id.with("move") == dragged;
+    let canvas = State::get_with_ctx(ctx)
+        .node_rect(&node_id)
+        .and_then(|node| node.canvas)
+        .unwrap_or(Id::NULL);
+    handle(node_id)
+        || is_node_selected_on(ctx, canvas, node_id)
+            && selected_nodes_on(ctx, canvas).into_iter().any(handle)
+}
+
+fn node_rects(ctx: &egui::Context, node_ids: &[NodeId]) -> Vec<(NodeId, Rect)> {
+    node_ids
+        .iter()
+        .filter_map(|node_id| {
+            ctx.memory(|memory| memory.area_rect(*node_id))
+                .map(|rect| (*node_id, rect))
+        })
+        .collect()
+}
+
+pub(crate) fn move_nodes(ctx: &egui::Context, moves: Vec<(NodeId, Pos2)>) -> Vec<(NodeId, Pos2)> {
+    ctx.data_mut(|data| {
+        let pending = data.get_temp_mut_or_default::<PendingMoves>(Id::NULL);
+        pending.0.extend(moves.iter().copied());
+    });
+    moves
+}
+
+fn take_pending_move(ctx: &egui::Context, node_id: NodeId) -> Option<Pos2> {
+    ctx.data_mut(|data| {
+        data.get_temp_mut_or_default::<PendingMoves>(Id::NULL)
+            .0
+            .remove(&node_id)
+    })
+}
+
+/// Computes how far the selected nodes on the canvas move together in this frame, before any of them is shown.
+/// A non-panned canvas keeps the dragged node inside the rect of the canvas, so the others move as far as it does.
+pub(crate) fn update_group_drag(ctx: &egui::Context, canvas: Id, viewport: Viewport) {
+    let key = canvas.with("group-drag");
+    let dragged = ctx.dragged_id();
+    let node_id = selected_nodes_on(ctx, canvas)
+        .into_iter()
+        .find(|node_id| dragged == Some(node_id.with("move")));
+    let rect = node_id.and_then(|node_id| ctx.memory(|memory| memory.area_rect(node_id)));
+    let (Some(node_id), Some(rect)) = (node_id, rect) else {
+        ctx.data_mut(|data| data.remove::<GroupDrag>(key));
+        return;
+    };
+    let scaling = viewport
+        .transform
+        .map_or(1.0, |transform| transform.scaling);
+    let pointer_delta = ctx.input(|input| input.pointer.delta()) / scaling;
+    let mut delta = pointer_delta;
+    if viewport.transform.is_none() {
+        let view = viewport.rect;
+        for d in 0..2 {
+            delta[d] = delta[d]
+                .min(view.max[d] - rect.max[d])
+                .max(view.min[d] - rect.min[d]);
+        }
+    }
+    let drag = GroupDrag {
+        node_id,
+        pointer_delta,
+        delta,
+    };
+    ctx.data_mut(|data| data.insert_temp(key, drag));
+}
+
+fn group_drag(ctx: &egui::Context, canvas: Id) -> Option<GroupDrag> {
+    ctx.data(|data| data.get_temp::<GroupDrag>(canvas.with("group-drag")))
+}
+
+/// Takes the nodes dropped in this frame on the canvas.
+pub(crate) fn take_moved_nodes(ctx: &egui::Context, canvas: Id) -> Option<Vec<NodeId>> {
+    ctx.data_mut(|data| data.remove_temp(canvas.with("nodes-moved")))
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{collapsing_header::CollapsingState, Event, Modifiers};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    /// Renders a node with a cable from its input to its output,
+    /// and returns the rects of the node, the body, the in-plug and the out-plug.
+    fn run(ctx: &egui::Context) -> [Rect; 4] {
+        let canvas = CableCanvas::new("canvas");
+        run_canvas(ctx, canvas, Modifiers::NONE, vec![], |ui| {
+            let node = Node::new("node", "Node")
+                .default_pos([100.0, 100.0])
+                .input(Port::new(0), "in")
+                .output(Port::new(1), "out")
+                .show(ui.ctx(), |ui| ui.label("body"))
+                .unwrap();
+            let body = node.inner.map_or(Rect::NOTHING, |body| body.rect);
+            let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+            [
+                node.response.rect,
+                body,
+                response.in_plug().rect,
+                response.out_plug().rect,
+            ]
+        })
+        .inner
+    }
+
+    #[test]
+    fn ports_on_the_sides() {
+        let ctx = egui::Context::default();
+        ctx.style_mut(|style| style.animation_time = 0.0);
+        // the window takes a few frames to fit the contents
+        for _ in 0..3 {
+            run(&ctx);
+        }
+        let [node, body, in_plug, out_plug] = run(&ctx);
+        // plugs sit on the outer edges of the port columns
+        assert!(in_plug.center().x < body.left());
+        assert!(out_plug.center().x > body.right());
+        assert!(node.contains(in_plug.center()) && node.contains(out_plug.center()));
+
+        // collapse it
+        let id = Id::new("node").with("collapsing");
+        let mut collapsing = CollapsingState::load_with_default_open(&ctx, id, true);
+        collapsing.set_open(false);
+        collapsing.store(&ctx);
+        run(&ctx);
+        let [title_bar, _, in_plug, out_plug] = run(&ctx);
+        assert!(title_bar.height() < node.height());
+        assert!(in_plug.center().distance(title_bar.left_center()) < 1.0);
+        assert!(out_plug.center().distance(title_bar.right_center()) < 1.0);
+    }
+
+    #[test]
+    fn cables_bend_around_nodes() {
+        let ctx = egui::Context::default();
+        let canvas = || CableCanvas::new("canvas");
+        // the window takes a few frames to fit the contents
+        let mut shown = None;
+        for _ in 0..4 {
+            let response = run_canvas(&ctx, canvas(), Modifiers::NONE, vec![], |ui| {
+                Node::new("a", "A")
+                    .default_pos([100.0, 100.0])
+                    .output(Port::new(0), "out")
+                    .show(ui.ctx(), |_| ());
+                Node::new("b", "B")
+                    .default_pos([500.0, 100.0])
+                    .input(Port::new(1), "in")
+                    .show(ui.ctx(), |_| ());
+                // in the way of the cable
+                let between = Node::new("between", "Between")
+                    .default_pos([250.0, 60.0])
+                    .show(ui.ctx(), |ui| ui.add_space(120.0))
+                    .unwrap();
+                let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+                (
+                    between.response.rect,
+                    response.in_plug().rect.center(),
+                    response.out_plug().rect.center(),
+                )
+            });
+            shown = Some(response.inner);
+        }
+        let (between, from, to) = shown.unwrap();
+        let straight = crate::path::PathShape::Bezier([from, from.lerp(to, 0.5), to]);
+        assert!(straight.intersects_rect(between));
+        let path = State::get_with_ctx(&ctx)
+            .cable_paths()
+            .into_iter()
+            .find(|path| path.cable_id == Id::new(0))
+            .unwrap();
+        assert!(!path.shape.intersects_rect(between));
+    }
+
+    /// Renders two nodes with a cable between them, and returns the rects of the nodes,
+    /// the rect of the plug on the second one and the nodes moved in this frame.
+    fn run_pair(ctx: &egui::Context, events: Vec<Event>) -> ([Rect; 3], Vec<NodeId>) {
+        let canvas = CableCanvas::new("canvas");
+        let response = run_canvas(ctx, canvas, Modifiers::NONE, events, |ui| {
+            let a = Node::new("a", "A")
+                .default_pos([100.0, 100.0])
+                .output(Port::new(0), "out")
+                .show(ui.ctx(), |_| ());
+            let b = Node::new("b", "B")
+                .default_pos([300.0, 300.0])
+                .input(Port::new(1), "in")
+                .show(ui.ctx(), |_| ());
+            let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+            [
+                a.unwrap().response.rect,
+                b.unwrap().response.rect,
+                response.out_plug().rect,
+            ]
+        });
+        let moved = response.nodes_moved().unwrap_or_default().to_vec();
+        (response.inner, moved)
+    }
+
+    #[test]
+    fn drag_and_align_selected_nodes() {
+        let ctx = egui::Context::default();
+        for _ in 0..3 {
+            run_pair(&ctx, vec![]);
+        }
+        let ([a, b, plug], _) = run_pair(&ctx, vec![]);
+        let (a_id, b_id) = (Id::new("a"), Id::new("b"));
+        crate::selection::select_nodes(&ctx, "canvas", [a_id, b_id]);
+
+        // drag the title bar of the first one
+        let from = a.left_top() + vec2(40.0, 8.0);
+        let to = from + vec2(100.0, 50.0);
+        run_pair(&ctx, vec![Event::PointerMoved(from), button(from, true)]);
+        let ([a_moved, b_moved, plug_moved], _) = run_pair(&ctx, vec![Event::PointerMoved(to)]);
+        // moved together in the same frame
+        assert_eq!(a_moved.min - a.min, vec2(100.0, 50.0));
+        assert_eq!(b_moved.min - b.min, vec2(100.0, 50.0));
+        assert_eq!(plug_moved.min - plug.min, vec2(100.0, 50.0));
+        let (_, moved) = run_pair(&ctx, vec![button(to, false)]);
+        assert_eq!(moved, vec![a_id, b_id]);
+
+        let positions = align_nodes(&ctx, &[a_id, b_id], NodeAlign::Top);
+        let ([a, b, _], _) = run_pair(&ctx, vec![]);
+        assert_eq!(a.top(), b.top());
+        assert_eq!(positions, vec![(a_id, a.min), (b_id, b.min)]);
+    }
+
+    #[test]
+    fn follow_the_dragged_node_kept_in_the_canvas() {
+        let ctx = egui::Context::default();
+        for _ in 0..3 {
+            run_pair(&ctx, vec![]);
+        }
+        let ([a, b, _], _) = run_pair(&ctx, vec![]);
+        crate::selection::select_nodes(&ctx, "canvas", [Id::new("a"), Id::new("b")]);
+
+        // drag the first one past the left edge of the canvas
+        let from = a.left_top() + vec2(40.0, 8.0);
+        let to = from - vec2(200.0, 0.0);
+        run_pair(&ctx, vec![Event::PointerMoved(from), button(from, true)]);
+        let ([a_moved, b_moved, _], _) = run_pair(&ctx, vec![Event::PointerMoved(to)]);
+        assert!(a_moved.min.x > a.min.x - 200.0);
+        assert_eq!(b_moved.min - b.min, a_moved.min - a.min);
+        run_pair(&ctx, vec![button(to, false)]);
+
+        // the first one, shown before the dragged second one, moves with it in the same frame
+        let ([a, b, _], _) = run_pair(&ctx, vec![]);
+        let from = b.left_top() + vec2(40.0, 8.0);
+        let to = from + vec2(50.0, 20.0);
+        run_pair(&ctx, vec![Event::PointerMoved(from)]);
+        run_pair(&ctx, vec![button(from, true)]);
+        let ([a_moved, b_moved, _], _) = run_pair(&ctx, vec![Event::PointerMoved(to)]);
+        assert_eq!(b_moved.min - b.min, vec2(50.0, 20.0));
+        assert_eq!(a_moved.min - a.min, vec2(50.0, 20.0));
+        let ([a_dropped, _, _], _) = run_pair(&ctx, vec![button(to, false)]);
+        assert_eq!(a_dropped, a_moved);
+    }
+}
@@ -0,0 +1,217 @@
+use egui::{Id, Pos2, Rect, Vec2};
+
+use crate::cable::CableId;
+
+/// The shape of a rendered cable, used for hit-testing knife strokes and selection boxes.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PathShape {
+    Bezier([Pos2; 3]),
+    // A loop cable plugged into the same port twice
+    Circle { center: Pos2, radius: f32 },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CablePath {
+    pub cable_id: CableId,
+    // The canvas the cable is rendered in
+    pub canvas: Option<Id>,
+    pub shape: PathShape,
+}
+
+// Number of line segments to approximate a curve with
+const SEGMENTS: usize = 32;
+// The part of a cable near its ends may cross obstacles, as ports sit inside the nodes.
+const END_REACH: f32 = 30.0;
+// How far the control point moves for each try when bending a cable around obstacles
+const BEND_STEP: f32 = 20.0;
+const MAX_BENDS: usize = 40;
+// The room left between a bent cable and an obstacle
+const GAP: f32 = 4.0;
+
+impl PathShape {
+    /// Returns the path approximated by line segments.
+    pub fn polyline(&self) -> Vec<Pos2> {
+        (0..=SEGMENTS)
+            .map(|i| {
+                let t = i as f32 / SEGMENTS as f32;
+                match self {
+                    PathShape::Bezier(points) => {
+                        let h = 1.0 - t;
+                        (points[0].to_vec2() * h * h
+                            + points[1].to_vec2() * 2.0 * t * h
+                            + points[2].to_vec2() * t * t)
+                            .to_pos2()
+                    }
+                    PathShape::Circle { center, radius } => {
+                        *center + Vec2::angled(t * std::f32::consts::TAU) * *radius
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Returns true if the segment from `a` to `b` crosses the path.
+    pub(crate) fn intersects_segment(&self, a: Pos2, b: Pos2) -> bool {
+        match self {
+            PathShape::Bezier(_) => self
+                .polyline()
+                .windows(2)
+                .any(|line| segments_intersect(a, b, line[0], line[1])),
+            PathShape::Circle { center, radius } => {
+                // The segment must get closer to the center than the radius without staying inside.
+                let ab = b - a;
+                let t = if ab.length_sq() > 0.0 {
+                    ((*center - a).dot(ab) / ab.length_sq()).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                let closest = a + ab * t;
+                closest.distance(*center) <= *radius
+                    && a.distance(*center).max(b.distance(*center)) >= *radius
+            }
+        }
+    }
+
+    /// Returns true if any part of the path is inside the rect.
+    pub(crate) fn intersects_rect(&self, rect: Rect) -> bool {
+        let corners = corners(rect);
+        self.polyline().iter().any(|pos| rect.contains(*pos))
+            || (0..4).any(|i| self.intersects_segment(corners[i], corners[(i + 1) % 4]))
+    }
+}
+
+fn corners(rect: Rect) -> [Pos2; 4] {
+    [
+        rect.left_top(),
+        rect.right_top(),
+        rect.right_bottom(),
+        rect.left_bottom(),
+    ]
+}
+
+fn segment_crosses_rect(a: Pos2, b: Pos2, rect: Rect) -> bool {
+    let corners = corners(rect);
+    rect.contains(a)
+        || rect.contains(b)
+        || (0..4).any(|i| segments_intersect(a, b, corners[i], corners[(i + 1) % 4]))
+}
+
+/// Returns the control point of a curve between the ends which doesn't cross the obstacles,
+/// moving the given one along the normal of the ends as little as possible, first to the side it bends to.
+/// The given control point is kept if no curve avoids them.
+pub(crate) fn route_around(from: Pos2, control: Pos2, to: Pos2, obstacles: &[Rect]) -> Pos2 {
+    let away = |pos: &Pos2| pos.distance(from) > END_REACH && pos.distance(to) > END_REACH;
+    let crosses = |control: Pos2| {
+        PathShape::Bezier([from, control, to])
+            .polyline()
+            .windows(2)
+            .filter(|line| away(&line[0]) && away(&line[1]))
+            .any(|line| {
+                obstacles
+                    .iter()
+                    .any(|rect| segment_crosses_rect(line[0], line[1], rect.expand(GAP)))
+            })
+    };
+    let normal = (to - from).rot90().normalized();
+    if from == to || !crosses(control) {
+        return control;
+    }
+    let side = if (control - from.lerp(to, 0.5)).dot(normal) < 0.0 {
+        -1.0
+    } else {
+        1.0
+    };
+    (1..=MAX_BENDS)
+        .flat_map(|i| [side, -side].map(|side| control + normal * side * BEND_STEP * i as f32))
+        .find(|control| !crosses(*control))
+        .unwrap_or(control)
+}
+
+fn segments_intersect(a: Pos2, b: Pos2, c: Pos2, d: Pos2) -> bool {
+    let cross = |o: Pos2, p: Pos2, q: Pos2| (p - o).x * (q - o).y - (p - o).y * (q - o).x;
+    let d1 = cross(c, d, a);
+    let d2 = cross(c, d, b);
+    let d3 = cross(a, b, c);
+    let d4 = cross(a, b, d);
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+    // touching counts as crossing
+    let on_segment = |o: Pos2, p: Pos2, q: Pos2| Rect::from_two_pos(o, p).contains(q);
+    (d1 == 0.0 && on_segment(c, d, a))
+        || (d2 == 0.0 && on_segment(c, d, b))
+        || (d3 == 0.0 && on_segment(a, b, c))
+        || (d4 == 0.0 && on_segment(a, b, d))
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::pos2;
+
+    use super::*;
+
+    #[test]
+    fn intersects_segment() {
+        let bezier = PathShape::Bezier([pos2(0.0, 0.0), pos2(50.0, 100.0), pos2(100.0, 0.0)]);
+        // crosses the bottom of the curve
+        assert!(bezier.intersects_segment(pos2(50.0, 0.0), pos2(50.0, 100.0)));
+        // above the curve between the ends
+        assert!(!bezier.intersects_segment(pos2(20.0, 0.0), pos2(80.0, 0.0)));
+
+        let circle = PathShape::Circle {
+            center: pos2(0.0, 0.0),
+            radius: 10.0,
+        };
+        assert!(circle.intersects_segment(pos2(-20.0, 0.0), pos2(20.0, 0.0)));
+        assert!(circle.intersects_segment(pos2(0.0, 0.0), pos2(20.0, 0.0)));
+        // inside
+        assert!(!circle.intersects_segment(pos2(-5.0, 0.0), pos2(5.0, 0.0)));
+        // outside
+        assert!(!circle.intersects_segment(pos2(-20.0, 20.0), pos2(20.0, 20.0)));
+    }
+
+    #[test]
+    fn intersects_rect() {
+        let bezier = PathShape::Bezier([pos2(0.0, 0.0), pos2(50.0, 100.0), pos2(100.0, 0.0)]);
+        let rect = |min, max| Rect::from_min_max(min, max);
+        // contains the bottom of the curve
+        assert!(bezier.intersects_rect(rect(pos2(40.0, 40.0), pos2(60.0, 60.0))));
+        // between the ends above the curve
+        assert!(!bezier.intersects_rect(rect(pos2(40.0, 0.0), pos2(60.0, 10.0))));
+
+        let circle = PathShape::Circle {
+            center: pos2(0.0, 0.0),
+            radius: 10.0,
+        };
+        // a thin rect crossing the circle between sampled points
+        assert!(circle.intersects_rect(rect(pos2(9.0, -100.0), pos2(9.5, 100.0))));
+        // inside the circle
+        assert!(!circle.intersects_rect(rect(pos2(-1.0, -1.0), pos2(1.0, 1.0))));
+    }
+
+    #[test]
+    fn route_around() {
+        let (from, to) = (pos2(0.0, 0.0), pos2(200.0, 0.0));
+        let straight = pos2(100.0, 0.0);
+        // nothing in the way
+        assert_eq!(super::route_around(from, straight, to, &[]), straight);
+
+        let obstacle = Rect::from_min_max(pos2(80.0, -20.0), pos2(120.0, 10.0));
+        let control = super::route_around(from, straight, to, &[obstacle]);
+        assert_ne!(control, straight);
+        assert!(!PathShape::Bezier([from, control, to]).intersects_rect(obstacle));
+        // a curve bent downward goes below the obstacle
+        let control = super::route_around(from, pos2(100.0, 10.0), to, &[obstacle]);
+        assert!(control.y > 0.0);
+        assert!(!PathShape::Bezier([from, control, to]).intersects_rect(obstacle));
+
+        // the ends may be inside obstacles, like ports inside nodes
+        let around_from = Rect::from_center_size(from, egui::vec2(40.0, 40.0));
+        assert_eq!(
+            super::route_around(from, straight, to, &[around_from]),
+            straight
+        );
+    }
+}
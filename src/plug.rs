@@ -1,14 +1,26 @@
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
 use std::fmt::Debug;
 use std::hash::Hash;
 
-use egui::{vec2, Order, Pos2, Vec2, Widget};
+use egui::{vec2, Key, Pos2, TouchPhase, Vec2, Widget};
 
 use crate::{
-    cable::CableId, custom_widget::CustomWidget, default_plug::DefaultPlug, event::Event,
-    plug_params::PlugParams, prelude::PortId, state::State,
+    accessibility::{announce, port_label},
+    cable::CableId,
+    canvas::{CableLayering, CanvasSettings, ConnectionMode, PlugFan},
+    context_menu::{self, ContextMenu},
+    custom_widget::CustomWidget,
+    default_plug::DefaultPlug,
+    event::Event,
+    grid::SnapGrid,
+    plug_params::PlugParams,
+    prelude::PortId,
+    state::State,
+    touch::{touch_events, PointerId, TouchGrab},
+    utils::{show_on_layer, SIZE},
 };
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum PlugType {
     In,
     Out,
@@ -35,6 +47,7 @@ pub struct Plug {
     pos: Option<Pos2>,
     widget: Option<CustomWidget>,
     locked: bool,
+    context_menu: Option<ContextMenu>,
     // inserted by Cable widget
     id: Option<PlugId>,
     // inserted by Cable widget
@@ -47,8 +60,25 @@ pub struct Plug {
 
 #[derive(Debug, Clone)]
 pub(crate) struct DraggedPlug {
+    pub pointer: PointerId,
     pub pos: Pos2,
     pub size: Vec2,
+    pub canvas: Option<egui::Id>,
+    // armed plugs don't pan the view, or a plug armed on a port near the edge would keep panning
+    pub auto_pan: bool,
+}
+
+/// A plug picked up by a click in click-click mode.
+#[derive(Debug, Clone)]
+pub(crate) struct ArmedPlug {
+    pub plug_id: PlugId,
+    // a click in the same frame must not complete the gesture
+    pub since_frame: u64,
+    // restored when the gesture is cancelled
+    pub origin: Pos2,
+    pub size: Vec2,
+    // keyboard-armed plug follows the focused port instead of the pointer
+    pub by_keyboard: bool,
 }
 
 impl Plug {
@@ -83,6 +113,13 @@ impl Plug {
         self
     }
 
+    /// Adds entries to the context menu of the plug, above the built-in ones.
+    /// A plug plugged into a port gets right-clicks only while its cable is selected.
+    pub fn context_menu(mut self, add_contents: impl FnOnce(&mut egui::Ui) + 'static) -> Self {
+        self.context_menu = Some(ContextMenu::new(add_contents));
+        self
+    }
+
     // used by cable
     pub(crate) fn default_pos_no_overwrite(mut self, pos: Pos2) -> Self {
         if self.default_pos.is_none() {
@@ -114,6 +151,27 @@ impl Plug {
 pub(crate) struct PlugState {
     pos_offset: Vec2,
     dragged: bool,
+    // position when the current drag started
+    drag_origin: Pos2,
+    returning: Option<Returning>,
+    // size in the last frame, used for fanning out plugs before rendering
+    size: Vec2,
+}
+
+impl PlugState {
+    /// Returns true if the plug is neither dragged nor going back.
+    pub(crate) fn is_resting(&self) -> bool {
+        !self.dragged && self.returning.is_none()
+    }
+}
+
+/// Animation of a plug going back after its drag is cancelled.
+#[derive(Clone, Debug)]
+struct Returning {
+    from: Pos2,
+    // `None` for returning to the plugged port
+    to: Option<Pos2>,
+    start_time: f64,
 }
 
 impl Widget for Plug {
@@ -121,12 +179,33 @@ impl Widget for Plug {
         // safe unwrap
         let id = self.id.unwrap();
         let default_pos = self.default_pos.unwrap();
+        let frame = ui.ctx().frame_nr();
+        let connection_mode = CanvasSettings::current(ui.ctx()).connection_mode;
 
         let mut state = State::get_cloned(ui);
         let mut plug_state = state.plug_state(&id).unwrap_or(PlugState {
             pos_offset: vec2(0.0, 0.0),
             dragged: false,
+            drag_origin: Pos2::ZERO,
+            returning: None,
+            size: SIZE,
         });
+        let armed = state.armed_plug().filter(|armed| armed.plug_id == id);
+        let plugged = self.plug_to.is_some() && !self.locked;
+        let default_context_menu =
+            CanvasSettings::current(ui.ctx()).default_context_menus && plugged;
+        let touch_events = touch_events(ui.ctx());
+        // a touch holding this plug, with its latest position
+        let touch_grab = state
+            .touch_grabs()
+            .into_iter()
+            .find(|grab| grab.plug_id == id)
+            .map(|mut grab| {
+                for event in touch_events.iter().filter(|e| e.pointer == grab.pointer) {
+                    grab.pos = event.pos;
+                }
+                grab
+            });
         let get_pos = || {
             if let Some(pos) = self.pos {
                 pos
@@ -134,73 +213,328 @@ impl Widget for Plug {
                 default_pos + plug_state.pos_offset
             }
         };
-        let mut pos = if plug_state.dragged {
+        let pointer_pos = ui
+            .input(|input| input.pointer.hover_pos())
+            .map(|pos| CanvasSettings::current(ui.ctx()).to_canvas(pos));
+        // plugs sharing the port with this plug, including this one
+        let stack = self
+            .plug_to
+            .as_ref()
+            .map(|port_id| plugs_on_port(&state, port_id))
+            .unwrap_or_default();
+        let stack_index = stack.iter().position(|plug_id| *plug_id == id).unwrap_or(0);
+        let plug_fan = CanvasSettings::current(ui.ctx()).plug_fan;
+        let stack_top = self.plug_to.as_ref().and_then(|port_id| {
+            (plug_fan == PlugFan::Stack && stack.len() > 1)
+                .then(|| state.stack_top(port_id).unwrap_or(0) % stack.len())
+        });
+        let port_anchor = self
+            .plug_to
+            .as_ref()
+            .and_then(|port_id| state.port_anchor(port_id));
+        let rest_pos = port_anchor
+            .as_ref()
+            .map(|anchor| {
+                anchor.plug_pos(plug_state.size)
+                    + fan_offset(plug_fan, stack_index, stack.len(), plug_state.size)
+            })
+            // If port is not displayed, use saved plug pos
+            .unwrap_or_else(get_pos);
+        let now = ui.input(|input| input.time);
+        let animation_time = ui.style().animation_time as f64;
+        let mut pos = if let Some(grab) = &touch_grab {
+            grab.pos - grab.offset
+        } else if let Some(armed) = &armed {
+            if armed.by_keyboard {
+                state
+                    .focused_port(frame)
+                    .and_then(|port_id| state.port_anchor(&port_id))
+                    .map(|anchor| anchor.plug_pos(plug_state.size))
+                    .unwrap_or_else(get_pos)
+            } else {
+                // armed plug follows the pointer
+                pointer_pos
+                    .map(|pointer_pos| pointer_pos - armed.size / 2.0)
+                    .unwrap_or_else(get_pos)
+            }
+        } else if plug_state.dragged {
             get_pos()
+        } else if let Some(returning) = &plug_state.returning {
+            let t = ((now - returning.start_time) / animation_time).clamp(0.0, 1.0) as f32;
+            returning.from.lerp(returning.to.unwrap_or(rest_pos), t)
         } else {
-            self.plug_to
-                .as_ref()
-                .and_then(|port_id| state.port_pos(port_id))
-                // If port is not displayed, use saved plug pos
-                .unwrap_or_else(get_pos)
+            rest_pos
         };
-        egui::Area::new(egui::Id::new(id.clone()))
-            // must be top-left of the widget
-            .current_pos(pos)
-            // should be displayed on foreground
-            .order(Order::Foreground)
-            .show(ui.ctx(), |ui| {
+        if let Some(returning) = &plug_state.returning {
+            if now - returning.start_time < animation_time {
+                ui.ctx().request_repaint();
+            } else {
+                plug_state.returning = None;
+            }
+        }
+        let resting = touch_grab.is_none() && armed.is_none() && plug_state.is_resting();
+        // a plug resting on a port may be painted on the layer of the port
+        let layer = self
+            .plug_to
+            .as_ref()
+            .filter(|_| {
+                CanvasSettings::current(ui.ctx()).layering == CableLayering::Ports && resting
+            })
+            .and_then(|port_id| state.port_layer(port_id));
+        // A carried plug is shown on the port it would be dropped on, but keeps moving with the pointer.
+        let carried = touch_grab.is_some()
+            || plug_state.dragged
+            || armed.as_ref().is_some_and(|armed| !armed.by_keyboard);
+        let pointer = touch_grab
+            .as_ref()
+            .map_or(PointerId::Primary, |grab| grab.pointer);
+        let snapped = CanvasSettings::current(ui.ctx())
+            .snap_radius
+            .filter(|_| carried)
+            .and_then(|_| state.hovered_port_id(&pointer))
+            .and_then(|port_id| state.port_anchor(&port_id))
+            .map(|anchor| anchor.plug_pos(plug_state.size));
+        // pos must be top-left of the widget
+        show_on_layer(
+            ui.ctx(),
+            egui::Id::new(id.clone()),
+            layer,
+            snapped.unwrap_or(pos),
+            |area| area,
+            |ui| {
                 // render plug with params
                 PlugParams {
                     vector: self.vec,
                     active: self.cable_active,
                     plugged: self.plug_to.is_some(),
                     locked: self.locked,
+                    armed: armed.is_some(),
+                    context_menu: self.context_menu.is_some() || default_context_menu,
+                    hidden_port: port_anchor
+                        .as_ref()
+                        .filter(|_| resting)
+                        .and_then(|anchor| anchor.hidden_direction()),
                 }
                 .set(ui);
-                // Move the layer top for active plug
-                if self.cable_active {
+                // Move the layer top for active plug, and for the top of stacked plugs
+                let on_top = stack_top == Some(stack_index);
+                if layer.is_none() && (self.cable_active || armed.is_some() || on_top) {
                     ui.ctx().move_to_top(ui.layer_id());
                 }
                 let response = self.widget.unwrap_or_else(|| DefaultPlug.into()).ui(ui);
 
                 let size = response.rect.size();
+                plug_state.size = size;
+
+                // count badge of stacked plugs, clicking it brings the next plug to the top
+                if let Some(port_id) = self.plug_to.as_ref().filter(|_| on_top) {
+                    let center = response.rect.right_top();
+                    let badge = ui.interact(
+                        egui::Rect::from_center_size(center, vec2(12.0, 12.0)),
+                        response.id.with("stack-badge"),
+                        egui::Sense::click(),
+                    );
+                    let visuals = ui.visuals();
+                    ui.painter()
+                        .circle_filled(center, 6.0, visuals.selection.bg_fill);
+                    ui.painter().text(
+                        center,
+                        egui::Align2::CENTER_CENTER,
+                        stack.len(),
+                        egui::FontId::proportional(9.0),
+                        visuals.selection.stroke.color,
+                    );
+                    let top = stack_index + usize::from(badge.clicked());
+                    state.update_stack_top(port_id.clone(), top);
+                }
 
                 // handle drag
+                if response.drag_started() {
+                    plug_state.drag_origin = pos;
+                    plug_state.returning = None;
+                }
                 pos += response.drag_delta();
 
                 // this should not be response.rect.center_size for painting it correctly
                 let center_pos = pos + size / 2.0;
 
+                // Escape or right-click aborts the drag without any connection change
+                let cancel_pressed = ui.input(|input| {
+                    input.key_pressed(Key::Escape) || input.pointer.secondary_pressed()
+                });
+                let mut cancelled = false;
+                if response.dragged() && cancel_pressed {
+                    ui.ctx().stop_dragging();
+                    cancelled = true;
+                    plug_state.returning = Some(Returning {
+                        from: pos,
+                        to: self.plug_to.is_none().then_some(plug_state.drag_origin),
+                        start_time: now,
+                    });
+                }
+
                 // Update plug pos used for determining a port is hovered by plug
-                plug_state.dragged = response.dragged();
-                if plug_state.dragged {
-                    state.update_dragged_plug(DraggedPlug {
-                        pos: center_pos,
-                        size: response.rect.size(),
+                plug_state.dragged = response.dragged() && !cancelled;
+                if plug_state.dragged || armed.is_some() {
+                    state.update_dragged_plug(
+                        PointerId::Primary,
+                        DraggedPlug {
+                            pointer: PointerId::Primary,
+                            pos: center_pos,
+                            size,
+                            canvas: CanvasSettings::current(ui.ctx()).id,
+                            auto_pan: plug_state.dragged,
+                        },
+                    );
+                }
+
+                if response.drag_stopped() && !cancelled {
+                    let port_id = state.hovered_port_id(&PointerId::Primary);
+                    pos = drop_pos(ui.ctx(), pos, size, port_id.as_ref());
+                    if let Some(event) = drop_event(self.plug_to.as_ref(), port_id) {
+                        state.ephemeral.event_of_plug.insert(response.id, event);
+                    }
+                }
+
+                // click-click and keyboard connection
+                let port_click = state.port_click().filter(|click| click.frame + 1 >= frame);
+                let mut still_armed = false;
+                if let Some(armed) = armed {
+                    let dropped_on = port_click
+                        .filter(|click| click.frame > armed.since_frame)
+                        .map(|click| click.port_id);
+                    let completed = dropped_on.is_some()
+                        || frame > armed.since_frame
+                            && ui.input(|input| input.pointer.primary_clicked());
+                    if cancel_pressed {
+                        cancelled = true;
+                        plug_state.returning = Some(Returning {
+                            from: pos,
+                            to: self.plug_to.is_none().then_some(armed.origin),
+                            start_time: now,
+                        });
+                    } else if completed {
+                        let port_id =
+                            dropped_on.or_else(|| state.hovered_port_id(&PointerId::Primary));
+                        pos = drop_pos(ui.ctx(), pos, size, port_id.as_ref());
+                        if let Some(event) = drop_event(self.plug_to.as_ref(), port_id) {
+                            state.ephemeral.event_of_plug.insert(response.id, event);
+                        }
+                    }
+                    if cancel_pressed || completed {
+                        state.update_armed_plug(None);
+                        state.update_last_disarm_frame(frame);
+                    } else {
+                        still_armed = true;
+                        state.update_armed_plug(Some(ArmedPlug { size, ..armed }));
+                    }
+                } else if !self.locked && state.armed_plug().is_none() {
+                    let port_click = port_click.filter(|click| {
+                        self.plug_to.as_ref() == Some(&click.port_id)
+                            && Some(click.frame) > state.last_disarm_frame()
                     });
+                    let clicked =
+                        connection_mode == ConnectionMode::ClickClick && response.clicked();
+                    let entered =
+                        response.has_focus() && ui.input(|input| input.key_pressed(Key::Enter));
+                    if clicked || entered || port_click.is_some() {
+                        state.update_armed_plug(Some(ArmedPlug {
+                            plug_id: id.clone(),
+                            since_frame: frame,
+                            origin: pos,
+                            size,
+                            by_keyboard: entered
+                                || port_click.is_some_and(|click| click.by_keyboard),
+                        }));
+                    }
                 }
 
-                if response.drag_stopped() {
-                    match (self.plug_to, state.hovered_port_id()) {
-                        // Connect event
-                        (_, Some(port_id)) => {
-                            state
-                                .ephemeral
-                                .event_of_plug
-                                .insert(response.id, Event::Connected { port_id });
+                // touches other than the primary pointer move plugs independently
+                let mut touch_pointer = None;
+                if let Some(grab) = touch_grab {
+                    touch_pointer = Some(grab.pointer);
+                    let phase = touch_events
+                        .iter()
+                        .rev()
+                        .find(|event| event.pointer == grab.pointer)
+                        .map(|event| event.phase);
+                    match phase {
+                        Some(TouchPhase::End) => {
+                            let port_id = state.hovered_port_id(&grab.pointer);
+                            pos = drop_pos(ui.ctx(), pos, size, port_id.as_ref());
+                            if let Some(event) = drop_event(self.plug_to.as_ref(), port_id) {
+                                state.ephemeral.event_of_plug.insert(response.id, event);
+                            }
+                            state.update_touch_grab(grab.pointer, None);
+                        }
+                        Some(TouchPhase::Cancel) => {
+                            cancelled = true;
+                            plug_state.returning = Some(Returning {
+                                from: pos,
+                                to: self.plug_to.is_none().then_some(plug_state.drag_origin),
+                                start_time: now,
+                            });
+                            state.update_touch_grab(grab.pointer, None);
                         }
-                        // Disconnect event
-                        (Some(_), None) => {
-                            state
-                                .ephemeral
-                                .event_of_plug
-                                .insert(response.id, Event::Disconnected);
+                        _ => {
+                            state.update_dragged_plug(
+                                grab.pointer,
+                                DraggedPlug {
+                                    pointer: grab.pointer,
+                                    pos: center_pos,
+                                    size,
+                                    canvas: CanvasSettings::current(ui.ctx()).id,
+                                    auto_pan: true,
+                                },
+                            );
+                            if let Some(port_id) = state.hovered_port_id(&grab.pointer) {
+                                state
+                                    .ephemeral
+                                    .event_of_plug
+                                    .insert(response.id, Event::Hovered { port_id });
+                            }
+                            state.update_touch_grab(grab.pointer, Some(grab));
                         }
-                        _ => {}
+                    }
+                } else if response.sense.drag && !self.locked {
+                    let grabbed: Vec<PointerId> = state
+                        .touch_grabs()
+                        .iter()
+                        .map(|grab| grab.pointer)
+                        .collect();
+                    if let Some(start) = touch_events.iter().find(|event| {
+                        event.phase == TouchPhase::Start
+                            && response.rect.contains(event.pos)
+                            && !grabbed.contains(&event.pointer)
+                    }) {
+                        plug_state.drag_origin = pos;
+                        plug_state.returning = None;
+                        state.update_touch_grab(
+                            start.pointer,
+                            Some(TouchGrab {
+                                pointer: start.pointer,
+                                plug_id: id.clone(),
+                                offset: start.pos - pos,
+                                pos: start.pos,
+                            }),
+                        );
                     }
                 }
-                if let Some(port_id) = state.hovered_port_id() {
-                    if response.dragged() {
+
+                // tell screen reader users what happened
+                match state.ephemeral.event_of_plug.get(&response.id) {
+                    Some(Event::Connected { port_id }) => {
+                        announce(
+                            ui.ctx(),
+                            format!("Connected to {}", port_label(&state, port_id)),
+                        );
+                    }
+                    Some(Event::Disconnected) => announce(ui.ctx(), "Disconnected".into()),
+                    _ => {}
+                }
+
+                if let Some(port_id) = state.hovered_port_id(&PointerId::Primary) {
+                    if response.dragged() || still_armed {
                         state
                             .ephemeral
                             .event_of_plug
@@ -208,13 +542,370 @@ impl Widget for Plug {
                     }
                 }
 
+                if cancelled {
+                    state
+                        .ephemeral
+                        .event_of_plug
+                        .insert(response.id, Event::DragCancelled);
+                }
+
+                // context menu, unless the plug is moving
+                let mut disconnect = false;
+                let mut disconnect_all = None;
+                if !response.dragged() && !still_armed {
+                    let defaults = default_context_menu.then_some(|ui: &mut egui::Ui| {
+                        if ui.button("Disconnect").clicked() {
+                            disconnect = true;
+                            ui.close_menu();
+                        }
+                        // the port is under the plug and cannot be right-clicked
+                        let button = ui.button("Disconnect all");
+                        if button.clicked() {
+                            ui.close_menu();
+                        }
+                        disconnect_all = Some(button.id);
+                    });
+                    context_menu::show(&response, self.context_menu, defaults);
+                }
+                if let Some((port_id, button)) = self.plug_to.clone().zip(disconnect_all) {
+                    state.update_disconnect_all_button(port_id, button);
+                }
+                // "Disconnect all" in the menu of the port or of any plug on it.
+                // Clicks are known from the start of the frame, so plugs added before the menu see it too.
+                let disconnect_all_clicked = self
+                    .plug_to
+                    .as_ref()
+                    .and_then(|port_id| state.disconnect_all_button(port_id))
+                    .and_then(|button| ui.ctx().read_response(button))
+                    .is_some_and(|button| button.clicked());
+                if plugged && disconnect_all_clicked {
+                    disconnect = true;
+                }
+                if disconnect {
+                    state
+                        .ephemeral
+                        .event_of_plug
+                        .insert(response.id, Event::Disconnected);
+                }
+                if state.ephemeral.event_of_plug.contains_key(&response.id) {
+                    state
+                        .ephemeral
+                        .pointer_of_plug
+                        .insert(response.id, touch_pointer.unwrap_or(PointerId::Primary));
+                }
+
                 // finally store states
+                // a returning plug is stored at the destination to keep the animation target stable
+                let pos = plug_state
+                    .returning
+                    .as_ref()
+                    .and_then(|returning| returning.to)
+                    .unwrap_or(pos);
                 plug_state.pos_offset = pos - default_pos;
                 state.update_plug_state(id.clone(), plug_state);
                 state.store_to(ui);
 
                 response
+            },
+        )
+    }
+}
+
+/// Returns the plugs on the port in a stable order.
+fn plugs_on_port(state: &State, port_id: &PortId) -> Vec<PlugId> {
+    let mut plugs: Vec<PlugId> = state
+        .all_cable_ends()
+        .into_iter()
+        .flat_map(|ends| {
+            [(ends.in_port, PlugType::In), (ends.out_port, PlugType::Out)]
+                .into_iter()
+                .filter(|(port, _)| port.as_ref() == Some(port_id))
+                .map(move |(_, plug_type)| PlugId::new(ends.cable_id, plug_type))
+        })
+        .collect();
+    plugs.sort_by_key(|plug| (plug.cable_id.value(), plug.plug_type));
+    plugs
+}
+
+/// Returns the offset of a plug from its port to keep plugs on the same port apart.
+fn fan_offset(fan: PlugFan, index: usize, count: usize, size: Vec2) -> Vec2 {
+    if count < 2 {
+        return Vec2::ZERO;
+    }
+    match fan {
+        PlugFan::Stack => Vec2::ZERO,
+        PlugFan::Radial => {
+            let plug = size.max_elem();
+            // large enough for neighbors not to overlap
+            let radius = (plug * 0.75).max(plug / (2.0 * (PI / count as f32).sin()));
+            let angle = TAU * index as f32 / count as f32 - FRAC_PI_2;
+            Vec2::angled(angle) * radius
+        }
+        PlugFan::Row => vec2((index as f32 - (count - 1) as f32 / 2.0) * size.x, 0.0),
+    }
+}
+
+/// Returns where a plug dropped at the position stays, snapped to the grid of the canvas unless it is dropped on a port.
+fn drop_pos(ctx: &egui::Context, pos: Pos2, size: Vec2, port_id: Option<&PortId>) -> Pos2 {
+    match SnapGrid::current(ctx) {
+        Some(grid) if port_id.is_none() => grid.snap_center(pos, size),
+        _ => pos,
+    }
+}
+
+/// Returns the event caused by dropping a plug, by releasing a drag or by completing a click-click gesture.
+fn drop_event(plug_to: Option<&PortId>, hovered_port_id: Option<PortId>) -> Option<Event> {
+    match (plug_to, hovered_port_id) {
+        // Connect event
+        (_, Some(port_id)) => Some(Event::Connected { port_id }),
+        // Disconnect event
+        (Some(_), None) => Some(Event::Disconnected),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, vec2, Rect};
+
+    use super::*;
+    use crate::{
+        prelude::*,
+        response::PlugResponse,
+        test_utils::{self, button, pointer_button, run_canvas, two_ports},
+    };
+
+    fn pointer_events(pos: Pos2, pressed: bool) -> Vec<egui::Event> {
+        vec![egui::Event::PointerMoved(pos), button(pos, pressed)]
+    }
+
+    fn key_event(key: Key, modifiers: egui::Modifiers) -> Vec<egui::Event> {
+        vec![test_utils::key(key, modifiers)]
+    }
+
+    #[derive(Debug)]
+    struct PlugOutput {
+        rect: Rect,
+        connected_to: Option<PortId>,
+        cancelled: bool,
+    }
+
+    impl From<PlugResponse> for PlugOutput {
+        fn from(mut plug: PlugResponse) -> Self {
+            PlugOutput {
+                rect: plug.rect,
+                connected_to: plug.connected_to(),
+                cancelled: plug.drag_cancelled(),
+            }
+        }
+    }
+
+    /// Renders two ports and a cable plugged into the first one,
+    /// and returns the in-plug and the out-plug.
+    fn run(ctx: &egui::Context, events: Vec<egui::Event>) -> [PlugOutput; 2] {
+        let canvas = CableCanvas::new("canvas").connection_mode(ConnectionMode::ClickClick);
+        run_canvas(ctx, canvas, Default::default(), events, |ui| {
+            two_ports(ui);
+            let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::unplugged()));
+            [response.in_plug().into(), response.out_plug().into()]
+        })
+        .inner
+    }
+
+    #[test]
+    fn click_click_connection() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![]);
+        let [_, out_plug] = run(&ctx, vec![]);
+        // arm the plug
+        run(&ctx, pointer_events(out_plug.rect.center(), true));
+        run(&ctx, pointer_events(out_plug.rect.center(), false));
+        // the plug follows the pointer
+        let port_pos = pos2(308.0, 108.0);
+        run(&ctx, vec![egui::Event::PointerMoved(port_pos)]);
+        let [_, out_plug] = run(&ctx, vec![egui::Event::PointerMoved(port_pos)]);
+        assert!(out_plug.rect.contains(port_pos));
+        // click the port
+        run(&ctx, pointer_events(port_pos, true));
+        let [_, out_plug] = run(&ctx, pointer_events(port_pos, false));
+        assert_eq!(out_plug.connected_to, Some(PortId::new(1)));
+    }
+
+    #[test]
+    fn escape_cancels_drag() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![]);
+        let [_, origin] = run(&ctx, vec![]);
+        // drag the plug onto the port
+        run(&ctx, pointer_events(origin.rect.center(), true));
+        let port_pos = pos2(308.0, 108.0);
+        run(&ctx, vec![egui::Event::PointerMoved(port_pos)]);
+        run(&ctx, vec![egui::Event::PointerMoved(port_pos)]);
+        let [_, out_plug] = run(&ctx, key_event(Key::Escape, Default::default()));
+        assert_eq!(out_plug.connected_to, None);
+        assert!(out_plug.cancelled);
+        // releasing on the port does nothing
+        let [_, out_plug] = run(&ctx, pointer_events(port_pos, false));
+        assert_eq!(out_plug.connected_to, None);
+        // the plug goes back after the animation
+        for _ in 0..10 {
+            run(&ctx, vec![]);
+        }
+        let [_, out_plug] = run(&ctx, vec![]);
+        assert_eq!(out_plug.rect, origin.rect);
+    }
+
+    #[test]
+    fn keyboard_connection() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![]);
+        run(&ctx, vec![]);
+        // focus the first port and pick up the plug on it
+        run(&ctx, key_event(Key::Tab, Default::default()));
+        run(&ctx, key_event(Key::Enter, Default::default()));
+        // focus the second port, the plug follows the focus
+        run(&ctx, key_event(Key::ArrowRight, Default::default()));
+        run(&ctx, vec![]);
+        let [in_plug, _] = run(&ctx, vec![]);
+        assert_eq!(in_plug.rect.left_top(), pos2(300.0, 100.0));
+        // drop it
+        let [in_plug, _] = run(&ctx, key_event(Key::Enter, Default::default()));
+        assert_eq!(in_plug.connected_to, Some(PortId::new(1)));
+    }
+
+    #[test]
+    fn right_click_cancels_click_click() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![]);
+        let [_, origin] = run(&ctx, vec![]);
+        // arm the plug and move it away
+        run(&ctx, pointer_events(origin.rect.center(), true));
+        run(&ctx, pointer_events(origin.rect.center(), false));
+        let pos = pos2(200.0, 200.0);
+        run(&ctx, vec![egui::Event::PointerMoved(pos)]);
+        let right_click = pointer_button(
+            pos,
+            egui::PointerButton::Secondary,
+            true,
+            Default::default(),
+        );
+        let [_, out_plug] = run(&ctx, vec![right_click]);
+        assert_eq!(out_plug.connected_to, None);
+        assert!(out_plug.cancelled);
+        // the plug goes back instead of staying where it was disarmed
+        for _ in 0..10 {
+            run(&ctx, vec![]);
+        }
+        let [_, out_plug] = run(&ctx, vec![]);
+        assert_eq!(out_plug.rect, origin.rect);
+    }
+
+    #[test]
+    fn fan_out_plugs_on_the_same_port() {
+        let ctx = egui::Context::default();
+        let run = |fan| {
+            let canvas = CableCanvas::new("canvas").plug_fan(fan);
+            run_canvas(&ctx, canvas, Default::default(), vec![], |ui| {
+                two_ports(ui);
+                (0..3)
+                    .map(|cable| {
+                        let mut response = ui.add(Cable::new(cable, Plug::to(0), Plug::to(1)));
+                        response.in_plug().rect
+                    })
+                    .collect::<Vec<_>>()
             })
             .inner
+        };
+        run(PlugFan::Stack);
+        let stacked = run(PlugFan::Stack);
+        assert!(stacked.iter().all(|rect| rect.min == pos2(100.0, 100.0)));
+
+        run(PlugFan::Radial);
+        let radial = run(PlugFan::Radial);
+        for (i, a) in radial.iter().enumerate() {
+            // around the port without overlapping each other, positions are rounded to pixels
+            assert!((a.center().distance(pos2(108.0, 108.0)) - 12.0).abs() < 1.0);
+            for b in &radial[i + 1..] {
+                assert!(a.center().distance(b.center()) >= 16.0);
+            }
+        }
+    }
+
+    #[test]
+    fn anchored_plugs_and_lines() {
+        let ctx = egui::Context::default();
+        let run = || {
+            run_canvas(
+                &ctx,
+                CableCanvas::new("canvas"),
+                Default::default(),
+                vec![],
+                |ui| {
+                    let size = vec2(40.0, 20.0);
+                    let port = Port::new(0).anchor(egui::Align2::RIGHT_CENTER);
+                    ui.put(Rect::from_min_size(pos2(100.0, 100.0), size), port);
+                    let port = Port::new(1).line_anchor(egui::Align2::LEFT_CENTER);
+                    ui.put(Rect::from_min_size(pos2(300.0, 100.0), size), port);
+                    let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+                    vec![response.in_plug().rect, response.out_plug().rect]
+                },
+            )
+            .inner
+        };
+        run();
+        let rects = run();
+        // centered on the right edge, or at the top-left without an anchor
+        assert!(rects[0].center().distance(pos2(140.0, 110.0)) < 1.0);
+        assert_eq!(rects[1].min, pos2(300.0, 100.0));
+        // the line ends at the left edge of the second port
+        let path = State::get_with_ctx(&ctx).cable_paths().pop().unwrap();
+        let crate::path::PathShape::Bezier([from, _, to]) = path.shape else {
+            panic!("the ends are apart");
+        };
+        assert!(from.distance(rects[0].center()) < 1.0);
+        assert!(to.distance(pos2(300.0, 110.0)) < 1.0);
+    }
+
+    #[test]
+    fn plugs_on_hidden_ports_stay_at_the_edge() {
+        let ctx = egui::Context::default();
+        let run = || {
+            run_canvas(
+                &ctx,
+                CableCanvas::new("canvas"),
+                Default::default(),
+                vec![],
+                |ui| {
+                    let scroll = egui::ScrollArea::vertical()
+                        .max_height(100.0)
+                        .show(ui, |ui| {
+                            let first = ui.add(Port::new(0)).rect;
+                            ui.add_space(300.0);
+                            (first, ui.add(Port::new(1)).rect)
+                        });
+                    let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+                    let (first, second) = scroll.inner;
+                    vec![
+                        scroll.inner_rect,
+                        first,
+                        second,
+                        response.in_plug().rect,
+                        response.out_plug().rect,
+                    ]
+                },
+            )
+            .inner
+        };
+        run();
+        let [view, first, second, in_plug, out_plug] = run()[..] else {
+            panic!("five rects");
+        };
+        assert!(view.contains_rect(first) && !view.intersects(second));
+        assert_eq!(in_plug.min, first.min);
+        // held at the bottom of the scroll area, under the hidden port,
+        // and the clip rect of a scroll area has a small margin
+        assert_eq!(out_plug.center().x, second.center().x);
+        let margin = ctx.style().visuals.clip_rect_margin;
+        assert!((out_plug.center().y - view.bottom() - margin).abs() < 1.0);
     }
 }
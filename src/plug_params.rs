@@ -8,6 +8,12 @@ pub struct PlugParams {
     pub active: bool,
     pub plugged: bool,
     pub locked: bool,
+    /// Picked up by a click and following the pointer in click-click mode.
+    pub armed: bool,
+    /// The plug has a context menu, so it should sense clicks even if it is not draggable.
+    pub context_menu: bool,
+    /// The direction toward the port when the port is out of view and the plug is held at the edge of the view.
+    pub hidden_port: Option<Vec2>,
 }
 
 impl PlugParams {
@@ -1,34 +1,196 @@
 use std::fmt::Debug;
 use std::hash::Hash;
 
-use egui::{Vec2, Widget};
-use egui_hooks::UseHookExt as _;
+use egui::{Align2, Key, Pos2, Rect, Vec2, Widget};
 
 use crate::{
-    custom_widget::CustomWidget, default_port::DefaultPort, id::Id, plug::DraggedPlug,
-    port_params::PortParams, state::State,
+    accessibility::describe_port,
+    auto_pan,
+    canvas::{CanvasSettings, ConnectionMode},
+    context_menu::{self, ContextMenu},
+    custom_widget::CustomWidget,
+    default_port::DefaultPort,
+    id::Id,
+    plug::DraggedPlug,
+    port_params::PortParams,
+    port_scope,
+    state::State,
+    touch::PointerId,
 };
 
 pub type PortId = Id;
 
+/// A click (or Enter) on a port.
+/// It picks up one of the plugs on the port, or drops the armed plug on the port.
+#[derive(Debug, Clone)]
+pub(crate) struct PortClick {
+    pub port_id: PortId,
+    pub frame: u64,
+    pub by_keyboard: bool,
+}
+
+/// A point on a port, given by an alignment in the port rect and an extra offset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anchor {
+    pub align: Align2,
+    pub offset: Vec2,
+}
+
+impl Anchor {
+    pub fn new(align: Align2) -> Self {
+        Anchor {
+            align,
+            offset: Vec2::ZERO,
+        }
+    }
+
+    pub fn offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn pos_in(&self, rect: Rect) -> Pos2 {
+        self.align.pos_in_rect(&rect) + self.offset
+    }
+}
+
+impl From<Align2> for Anchor {
+    fn from(align: Align2) -> Self {
+        Anchor::new(align)
+    }
+}
+
+/// Where plugs rest on a rendered port and where the cable lines end.
+#[derive(Debug, Clone)]
+pub(crate) struct PortAnchor {
+    pub rect: Rect,
+    /// The visible region around the port, its clip rect within the clip rect of the canvas.
+    pub view: Rect,
+    pub plug: Option<Anchor>,
+    pub line: Option<Anchor>,
+    // the frame the port was shown in, or stood in for by a `PortScope`
+    pub frame: u64,
+    pub canvas: Option<egui::Id>,
+}
+
+impl PortAnchor {
+    /// Returns true if the port is out of the view, like scrolled out of a scroll area.
+    pub fn hidden(&self) -> bool {
+        !self.view.intersects(self.rect)
+    }
+
+    /// Returns the direction toward a hidden port from the edge of the view.
+    pub fn hidden_direction(&self) -> Option<Vec2> {
+        let edge = self.view.clamp(self.rect.center());
+        self.hidden()
+            .then(|| (self.rect.center() - edge).normalized())
+    }
+
+    /// Returns the top-left of a resting plug of the size.
+    /// Without a plug anchor, the plug sits at the top-left of the port.
+    /// Plugs on a hidden port are held at the closest point on the edge of the view.
+    pub fn plug_pos(&self, size: Vec2) -> Pos2 {
+        if self.hidden() {
+            return self.view.clamp(self.rect.center()) - size / 2.0;
+        }
+        self.plug
+            .map(|anchor| anchor.pos_in(self.rect) - size / 2.0)
+            .unwrap_or(self.rect.left_top())
+    }
+
+    /// Returns the offset from the center of a resting plug of the size to the end of the line.
+    pub fn line_offset(&self, size: Vec2) -> Option<Vec2> {
+        let plug_center = self.plug_pos(size) + size / 2.0;
+        self.line
+            .filter(|_| !self.hidden())
+            .map(|anchor| anchor.pos_in(self.rect) - plug_center)
+    }
+}
+
 #[derive(Debug)]
 pub struct Port {
     port_id: PortId,
+    label: Option<String>,
     widget: Option<CustomWidget>,
+    context_menu: Option<ContextMenu>,
+    plug_anchor: Option<Anchor>,
+    line_anchor: Option<Anchor>,
 }
 
 impl Port {
     pub fn new<T: Hash + Eq + Debug + Send + Sync + 'static>(port_id: T) -> Self {
         Port {
             port_id: PortId::new(port_id),
+            label: None,
             widget: None,
+            context_menu: None,
+            plug_anchor: None,
+            line_anchor: None,
         }
     }
 
+    /// Centers plugs on the point of the port, like `Align2::RIGHT_CENTER` for an output on the right edge.
+    /// Without this, plugs sit at the top-left of the port.
+    pub fn anchor(mut self, anchor: impl Into<Anchor>) -> Self {
+        self.plug_anchor = Some(anchor.into());
+        self
+    }
+
+    /// Ends the lines of plugged cables at the point of the port instead of the plug centers.
+    pub fn line_anchor(mut self, anchor: impl Into<Anchor>) -> Self {
+        self.line_anchor = Some(anchor.into());
+        self
+    }
+
+    pub(crate) fn id(&self) -> &PortId {
+        &self.port_id
+    }
+
+    /// Anchors plugs and lines at the point unless they have their own anchors.
+    pub(crate) fn default_anchor(mut self, align: Align2) -> Self {
+        self.plug_anchor.get_or_insert(align.into());
+        self.line_anchor.get_or_insert(align.into());
+        self
+    }
+
+    /// Sets the name read by screen readers. Defaults to the debug format of the port ID.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
     pub fn widget(mut self, widget: impl Into<CustomWidget>) -> Self {
         self.widget = Some(widget.into());
         self
     }
+
+    /// Adds entries to the context menu of the port, above the built-in ones.
+    /// Plugs on the port get right-clicks on them instead, and their menus offer "Disconnect all" too.
+    pub fn context_menu(
+        mut self,
+        add_contents: impl FnOnce(&mut egui::Ui, PortId) + 'static,
+    ) -> Self {
+        let port_id = self.port_id.clone();
+        self.context_menu = Some(ContextMenu::new(move |ui| add_contents(ui, port_id)));
+        self
+    }
+}
+
+/// Brings the next plug to the top of the plugs stacked on the port.
+pub fn cycle_plugs(ctx: &egui::Context, port_id: &PortId) {
+    State::update_with_ctx(ctx, |state| {
+        let top = state.stack_top(port_id).unwrap_or(0);
+        state.update_stack_top(port_id.clone(), top + 1);
+    });
+}
+
+/// Returns the visible region for ports shown in the ui.
+pub(crate) fn view(ui: &egui::Ui) -> Rect {
+    CanvasSettings::current(ui.ctx())
+        .clip_rect
+        .map_or(ui.clip_rect(), |clip_rect| {
+            clip_rect.intersect(ui.clip_rect())
+        })
 }
 
 impl Widget for Port {
@@ -40,37 +202,104 @@ impl Widget for Port {
 
             // Render port with params
             PortParams {
-                hovered: state.hovered_port_id() == Some(self.port_id.clone()),
+                hovered: state.is_port_hovered(&self.port_id),
             }
             .set(ui);
             let response = self.widget.unwrap_or_else(|| DefaultPort.into()).ui(ui);
 
             // advance generation if this port is rendered twice
             state.advance_generation_if_twice(self.port_id.clone());
-            // update port's position used for plug rendering
-            state.update_port_pos(self.port_id.clone(), response.rect.left_top());
+            // update port's anchors used for plug and cable rendering
+            let anchor = PortAnchor {
+                rect: response.rect,
+                view: view(ui),
+                plug: self.plug_anchor,
+                line: self.line_anchor,
+                frame: ui.ctx().frame_nr(),
+                canvas: CanvasSettings::current(ui.ctx()).id,
+            };
+            let hidden = anchor.hidden();
+            state.update_port_anchor(self.port_id.clone(), anchor);
+            state.update_port_layer(self.port_id.clone(), ui.layer_id());
+            port_scope::register_port(ui.ctx(), &self.port_id);
+            auto_pan::scroll_toward_plugs(ui, &state);
 
-            let dragged_plug = state.dragged_plug().unwrap_or(DraggedPlug {
-                pos: egui::pos2(-100.0, -100.0), // far
-                size: Vec2::ZERO,
-            });
+            // accessibility
+            let label = self.label.unwrap_or_else(|| self.port_id.label());
+            state.update_port_label(self.port_id.clone(), label.clone());
+            #[cfg(feature = "accesskit")]
+            state.update_port_widget_id(self.port_id.clone(), response.id);
+            describe_port(&state, &response, &self.port_id, &label);
+
+            // distance required because `response.hovered()` always returns false when plug is interacted
+            let settings = CanvasSettings::current(ui.ctx());
+            let reach = |dragged_plug: &DraggedPlug| {
+                // the shapes overlap, or the plug is within the snap radius on the screen
+                let min_length = |vec: Vec2| vec.x.min(vec.y);
+                let overlap =
+                    (min_length(response.rect.size()) + min_length(dragged_plug.size)) / 2.0;
+                settings
+                    .snap_radius
+                    .map_or(overlap, |radius| overlap.max(radius / settings.scaling()))
+            };
 
-            println!("dragged: {:?}", dragged_plug.pos);
-            println!("response: {:?}", response.rect.center());
+            // update hovered port id used for cable connection, for each pointer
+            let frame = ui.ctx().frame_nr();
+            if response.hovered() {
+                // no port is nearer than the one under the mouse
+                state.offer_hovered_port(PointerId::Primary, self.port_id.clone(), 0.0, frame);
+            }
+            // the nearest port in reach of a plug takes it, and a port out of the view can't take plugs
+            for dragged_plug in state.dragged_plugs() {
+                let distance = response.rect.center().distance(dragged_plug.pos);
+                if !hidden && distance < reach(&dragged_plug) {
+                    state.offer_hovered_port(
+                        dragged_plug.pointer,
+                        self.port_id.clone(),
+                        distance,
+                        frame,
+                    );
+                }
+            }
 
-            // distance between the port and the dragged plug
-            let distance_sq = response.rect.center().distance_sq(dragged_plug.pos);
-            let min_length = |vec: Vec2| vec.x.min(vec.y);
-            let close_distance =
-                (min_length(response.rect.size()) + min_length(dragged_plug.size)) / 2.0;
+            // keyboard-armed plugs follow the focused port
+            let focused = response.has_focus();
+            if focused {
+                state.update_focused_port(self.port_id.clone(), frame);
+            }
 
-            // distance required because `response.hovered()` always returns false when plug is interacted
-            let hovered = response.hovered() || distance_sq < close_distance.powi(2);
+            // Clicking a port in click-click mode, or pressing Enter on a focused port,
+            // picks up or drops a plug unless a gesture just ended in this frame.
+            let clicked = CanvasSettings::current(ui.ctx()).connection_mode
+                == ConnectionMode::ClickClick
+                && response.clicked();
+            let entered = focused && ui.input(|input| input.key_pressed(Key::Enter));
+            if (clicked || entered) && state.last_disarm_frame() != Some(frame) {
+                state.update_port_click(PortClick {
+                    port_id: self.port_id.clone(),
+                    frame,
+                    by_keyboard: entered,
+                });
+            }
 
-            // update hovered port id used for cable connection
-            if hovered {
-                println!("hoverd: {:?}", self.port_id);
-                state.update_hovered_port_id(self.port_id);
+            // context menu
+            let plugged = state.all_cable_ends().iter().any(|ends| {
+                ends.in_port.as_ref() == Some(&self.port_id)
+                    || ends.out_port.as_ref() == Some(&self.port_id)
+            });
+            let show_defaults = CanvasSettings::current(ui.ctx()).default_context_menus && plugged;
+            let mut disconnect_all = None;
+            let defaults = show_defaults.then_some(|ui: &mut egui::Ui| {
+                let button = ui.button("Disconnect all");
+                if button.clicked() {
+                    ui.close_menu();
+                }
+                disconnect_all = Some(button.id);
+            });
+            context_menu::show(&response, self.context_menu, defaults);
+            if let Some(button) = disconnect_all {
+                // plugs on this port watch the button, see `Plug`
+                state.update_disconnect_all_button(self.port_id.clone(), button);
             }
 
             // finally update the state
@@ -81,3 +310,54 @@ impl Widget for Port {
         .inner
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, Event, Modifiers};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    /// Renders two ports side by side and a cable from a third port on a canvas with a snap radius,
+    /// and returns the rect of the unplugged plug and the port it was connected to in this frame.
+    fn run(ctx: &egui::Context, events: Vec<Event>) -> (Rect, Option<PortId>) {
+        let canvas = CableCanvas::new("canvas").snap_radius(40.0);
+        run_canvas(ctx, canvas, Modifiers::NONE, events, |ui| {
+            put_ports(
+                ui,
+                [
+                    (0, pos2(100.0, 100.0)),
+                    (1, pos2(150.0, 100.0)),
+                    (2, pos2(100.0, 300.0)),
+                ],
+            );
+            let mut response = ui.add(Cable::new(0, Plug::to(2), Plug::unplugged()));
+            let mut plug = response.out_plug();
+            (plug.rect, plug.connected_to())
+        })
+        .inner
+    }
+
+    #[test]
+    fn snap_to_the_nearest_port() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![]);
+        let (plug, _) = run(&ctx, vec![]);
+
+        // within the radius of both ports but nearer to the first one, which is rendered first
+        let from = plug.center();
+        let to = pos2(125.0, 108.0);
+        run(&ctx, vec![Event::PointerMoved(from), button(from, true)]);
+        for i in 1..=4 {
+            run(
+                &ctx,
+                vec![Event::PointerMoved(from + (to - from) * i as f32 / 4.0)],
+            );
+        }
+        let (carried, _) = run(&ctx, vec![]);
+        assert_eq!(carried.min, pos2(100.0, 100.0));
+
+        let (_, connected) = run(&ctx, vec![button(to, false)]);
+        assert_eq!(connected, Some(PortId::new(0)));
+    }
+}
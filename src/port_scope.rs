@@ -0,0 +1,206 @@
+use std::hash::Hash;
+use std::sync::Arc;
+
+use egui::{Align2, Id, LayerId, Rect, Response, Ui};
+
+use crate::{
+    canvas::CanvasSettings,
+    node::NodeId,
+    port::{self, Anchor, PortAnchor, PortId},
+    state::State,
+};
+
+/// Stands in for the ports shown in it while they are not shown, like in a collapsed
+/// `CollapsingHeader` or a closed window, so cables to them end at the scope instead of dangling.
+///
+/// The scope remembers the ports shown inside it. Nested scopes are fine, and the innermost
+/// shown one stands in for a hidden port.
+#[derive(Debug, Clone)]
+pub struct PortScope {
+    id: Id,
+    anchor: Anchor,
+}
+
+// A scope being shown, with the ports and the nodes shown in it so far
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OpenScope {
+    pub ports: Vec<PortId>,
+    pub nodes: Vec<NodeId>,
+}
+
+// A newtype to give the members of a scope their own slot in the context data.
+#[derive(Debug, Clone, Default)]
+struct Members(Vec<PortId>);
+
+impl PortScope {
+    pub fn new(id_source: impl Hash) -> Self {
+        PortScope {
+            id: Id::new(id_source),
+            anchor: Align2::CENTER_CENTER.into(),
+        }
+    }
+
+    /// Sets where plugs of hidden ports rest on the returned response. Defaults to the center.
+    pub fn anchor(mut self, anchor: impl Into<Anchor>) -> Self {
+        self.anchor = anchor.into();
+        self
+    }
+
+    /// Shows the contents and stands in for the hidden ports at the rect of the returned response,
+    /// like the header response of a `CollapsingHeader`.
+    pub fn show(self, ui: &mut Ui, add_contents: impl FnOnce(&mut Ui) -> Response) -> Response {
+        let ctx = ui.ctx().clone();
+        open(&ctx);
+        let response = add_contents(ui);
+        let shown = close(&ctx).ports;
+
+        let mut members = ctx.data(|data| data.get_temp::<Members>(self.id).unwrap_or_default().0);
+        for port_id in &shown {
+            if !members.contains(port_id) {
+                members.push(port_id.clone());
+            }
+        }
+        let mut state = State::get_cloned(ui);
+        for port_id in members.iter().filter(|port_id| !shown.contains(port_id)) {
+            stand_in(
+                &mut state,
+                &ctx,
+                port_id,
+                StandIn {
+                    rect: response.rect,
+                    view: port::view(ui),
+                    anchor: self.anchor,
+                    layer: ui.layer_id(),
+                },
+            );
+        }
+        state.store_to(ui);
+        ctx.data_mut(|data| data.insert_temp(self.id, Members(members)));
+
+        response
+    }
+}
+
+/// Starts collecting the ports and the nodes shown until [`close`].
+pub(crate) fn open(ctx: &egui::Context) {
+    update_stack(ctx, |stack| stack.push(OpenScope::default()));
+}
+
+/// Returns the ports and the nodes shown since [`open`].
+pub(crate) fn close(ctx: &egui::Context) -> OpenScope {
+    update_stack(ctx, |stack| stack.pop()).unwrap_or_default()
+}
+
+/// Where plugs of a hidden port rest instead.
+pub(crate) struct StandIn {
+    pub rect: Rect,
+    pub view: Rect,
+    pub anchor: Anchor,
+    pub layer: LayerId,
+}
+
+/// Stands in for a hidden port unless it was shown in this frame.
+pub(crate) fn stand_in(
+    state: &mut State,
+    ctx: &egui::Context,
+    port_id: &PortId,
+    stand_in: StandIn,
+) {
+    let frame = ctx.frame_nr();
+    // shown out of the scope in this frame
+    if state
+        .port_anchor(port_id)
+        .is_some_and(|anchor| anchor.frame == frame)
+    {
+        return;
+    }
+    state.update_port_anchor(
+        port_id.clone(),
+        PortAnchor {
+            rect: stand_in.rect,
+            view: stand_in.view,
+            plug: Some(stand_in.anchor),
+            line: None,
+            frame,
+            canvas: CanvasSettings::current(ctx).id,
+        },
+    );
+    state.update_port_layer(port_id.clone(), stand_in.layer);
+    // outer scopes don't stand in for it
+    register_port(ctx, port_id);
+}
+
+/// Adds the port to the scopes being shown.
+pub(crate) fn register_port(ctx: &egui::Context, port_id: &PortId) {
+    update_stack(ctx, |stack| {
+        for scope in stack.iter_mut() {
+            if !scope.ports.contains(port_id) {
+                scope.ports.push(port_id.clone());
+            }
+        }
+    });
+}
+
+/// Adds the node to the scopes being shown.
+pub(crate) fn register_node(ctx: &egui::Context, node_id: NodeId) {
+    update_stack(ctx, |stack| {
+        for scope in stack.iter_mut() {
+            if !scope.nodes.contains(&node_id) {
+                scope.nodes.push(node_id);
+            }
+        }
+    });
+}
+
+fn update_stack<R>(ctx: &egui::Context, f: impl FnOnce(&mut Vec<OpenScope>) -> R) -> R {
+    ctx.data_mut(|data| {
+        let stack = data.get_temp_mut_or_default::<Arc<Vec<OpenScope>>>(Id::NULL);
+        f(Arc::make_mut(stack))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::pos2;
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    #[test]
+    fn stand_in_for_collapsed_ports() {
+        let ctx = egui::Context::default();
+        // collapse without animation
+        ctx.style_mut(|style| style.animation_time = 0.0);
+        // returns the header rect and the out-plug rect
+        let run = |open| {
+            run_canvas(
+                &ctx,
+                CableCanvas::new("canvas"),
+                Default::default(),
+                vec![],
+                |ui| {
+                    put_ports(ui, [(0, pos2(100.0, 300.0))]);
+                    let header = PortScope::new("group").show(ui, |ui| {
+                        egui::CollapsingHeader::new("group")
+                            .open(Some(open))
+                            .show(ui, |ui| ui.add(Port::new(1)))
+                            .header_response
+                    });
+                    let mut response = ui.add(Cable::new(0, Plug::to(0), Plug::to(1)));
+                    vec![header.rect, response.out_plug().rect]
+                },
+            )
+            .inner
+        };
+        run(true);
+        let [header, out_plug] = run(true)[..] else {
+            panic!("two rects");
+        };
+        assert!(header.bottom() <= out_plug.top());
+        run(false);
+        let [header, out_plug] = run(false)[..] else {
+            panic!("two rects");
+        };
+        assert!(out_plug.center().distance(header.center()) < 1.0);
+    }
+}
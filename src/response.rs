@@ -9,13 +9,19 @@ pub trait ResponseExt {
     fn in_plug(&mut self) -> PlugResponse;
     /// Returns a out-plug response
     fn out_plug(&mut self) -> PlugResponse;
+    /// Returns true if the user asked to remove the cable
+    fn delete_requested(&mut self) -> bool;
+    /// Returns true if the user asked to swap the ends of the cable
+    fn swap_ends_requested(&mut self) -> bool;
+    /// Returns `Some(true)` if the user asked to lock the cable, `Some(false)` to unlock it
+    fn lock_requested(&mut self) -> Option<bool>;
 }
 
 pub struct PlugResponse(pub(crate) Response);
 
 impl ResponseExt for Response {
     fn in_plug(&mut self) -> PlugResponse {
-        let response = State::get_with_ctx(&mut self.ctx)
+        let response = State::get_with_ctx(&self.ctx)
             .ephemeral
             .plug_responses_of_cable
             .get(&self.id)
@@ -26,7 +32,7 @@ impl ResponseExt for Response {
     }
 
     fn out_plug(&mut self) -> PlugResponse {
-        let response = State::get_with_ctx(&mut self.ctx)
+        let response = State::get_with_ctx(&self.ctx)
             .ephemeral
             .plug_responses_of_cable
             .get(&self.id)
@@ -35,11 +41,42 @@ impl ResponseExt for Response {
             .clone();
         PlugResponse(response)
     }
+
+    fn delete_requested(&mut self) -> bool {
+        matches!(
+            State::get_with_ctx(&self.ctx)
+                .ephemeral
+                .event_of_cable
+                .get(&self.id),
+            Some(Event::DeleteRequested { .. })
+        )
+    }
+
+    fn swap_ends_requested(&mut self) -> bool {
+        matches!(
+            State::get_with_ctx(&self.ctx)
+                .ephemeral
+                .event_of_cable
+                .get(&self.id),
+            Some(Event::SwapEndsRequested { .. })
+        )
+    }
+
+    fn lock_requested(&mut self) -> Option<bool> {
+        match State::get_with_ctx(&self.ctx)
+            .ephemeral
+            .event_of_cable
+            .get(&self.id)
+        {
+            Some(Event::LockRequested { lock, .. }) => Some(*lock),
+            _ => None,
+        }
+    }
 }
 
 impl PlugResponse {
     pub fn connected_to(&mut self) -> Option<PortId> {
-        let state = State::get_with_ctx(&mut self.0.ctx);
+        let state = State::get_with_ctx(&self.0.ctx);
         if let Some(Event::Connected { port_id }) = state.ephemeral.event_of_plug.get(&self.0.id) {
             Some(port_id.clone())
         } else {
@@ -48,15 +85,24 @@ impl PlugResponse {
     }
 
     pub fn disconnected(&mut self) -> bool {
-        let state = State::get_with_ctx(&mut self.0.ctx);
+        let state = State::get_with_ctx(&self.0.ctx);
+        matches!(
+            state.ephemeral.event_of_plug.get(&self.0.id),
+            Some(Event::Disconnected)
+        )
+    }
+
+    /// Returns true if the drag of this plug was aborted by Escape or right-click.
+    pub fn drag_cancelled(&mut self) -> bool {
+        let state = State::get_with_ctx(&self.0.ctx);
         matches!(
             state.ephemeral.event_of_plug.get(&self.0.id),
-            Some(Event::Disconnected { .. })
+            Some(Event::DragCancelled)
         )
     }
 
     pub fn hovered_on(&mut self) -> Option<PortId> {
-        let state = State::get_with_ctx(&mut self.0.ctx);
+        let state = State::get_with_ctx(&self.0.ctx);
         if let Some(Event::Hovered { port_id }) = state.ephemeral.event_of_plug.get(&self.0.id) {
             Some(port_id.clone())
         } else {
@@ -64,6 +110,12 @@ impl PlugResponse {
         }
     }
 
+    /// Returns the pointer or the touch which caused the event of this plug.
+    pub fn pointer(&mut self) -> Option<PointerId> {
+        let state = State::get_with_ctx(&self.0.ctx);
+        state.ephemeral.pointer_of_plug.get(&self.0.id).copied()
+    }
+
     pub fn next_position(&self) -> Pos2 {
         self.0.rect.left_top() + self.0.drag_delta()
     }
@@ -0,0 +1,358 @@
+use std::hash::Hash;
+
+use egui::{Id, Modifiers, PointerButton, Pos2, Rect, Response, Sense, Ui, Vec2};
+
+use crate::{cable::CableId, canvas::CanvasSettings, context_menu, node::NodeId, state::State};
+
+// A newtype for the selected cables of a canvas in the context data.
+#[derive(Debug, Clone, Default)]
+struct Selection(Vec<CableId>);
+
+// The same for nodes.
+#[derive(Debug, Clone, Default)]
+struct NodeSelection(Vec<NodeId>);
+
+/// Returns the selected cables on the canvas with the ID source, in the order they were selected.
+///
+/// Cables which have not been rendered recently, like removed ones, are skipped.
+///
+/// Deletion and locking act on the whole selection. Recoloring is left to the app, which owns the colors
+/// and applies them to these cables with [`Cable::color`](crate::cable::Cable::color).
+pub fn selected_cables(ctx: &egui::Context, canvas_id_source: impl Hash) -> Vec<CableId> {
+    selected_cables_on(ctx, Id::new(canvas_id_source))
+}
+
+/// Replaces the selection of cables on the canvas with the ID source.
+pub fn select_cables(
+    ctx: &egui::Context,
+    canvas_id_source: impl Hash,
+    cable_ids: impl IntoIterator<Item = CableId>,
+) {
+    select_cables_on(ctx, Id::new(canvas_id_source), cable_ids);
+}
+
+/// Clears the selection of cables and nodes on the canvas with the ID source.
+pub fn clear_selection(ctx: &egui::Context, canvas_id_source: impl Hash) {
+    clear_selection_on(ctx, Id::new(canvas_id_source));
+}
+
+pub fn is_cable_selected(
+    ctx: &egui::Context,
+    canvas_id_source: impl Hash,
+    cable_id: CableId,
+) -> bool {
+    is_cable_selected_on(ctx, Id::new(canvas_id_source), cable_id)
+}
+
+/// Returns the selected [`Node`](crate::node::Node)s on the canvas with the ID source, in the order they were selected.
+///
+/// Nodes which have not been shown recently, like removed ones, are skipped.
+pub fn selected_nodes(ctx: &egui::Context, canvas_id_source: impl Hash) -> Vec<NodeId> {
+    selected_nodes_on(ctx, Id::new(canvas_id_source))
+}
+
+/// Replaces the selection of nodes on the canvas with the ID source.
+pub fn select_nodes(
+    ctx: &egui::Context,
+    canvas_id_source: impl Hash,
+    node_ids: impl IntoIterator<Item = NodeId>,
+) {
+    select_nodes_on(ctx, Id::new(canvas_id_source), node_ids);
+}
+
+pub fn is_node_selected(ctx: &egui::Context, canvas_id_source: impl Hash, node_id: NodeId) -> bool {
+    is_node_selected_on(ctx, Id::new(canvas_id_source), node_id)
+}
+
+// The functions below take the canvas ID, which is `Id::NULL` for widgets outside of any canvas.
+
+pub(crate) fn selected_cables_on(ctx: &egui::Context, canvas: Id) -> Vec<CableId> {
+    let rendered: Vec<CableId> = State::get_with_ctx(ctx)
+        .cable_paths()
+        .into_iter()
+        .map(|path| path.cable_id)
+        .collect();
+    selection(ctx, canvas)
+        .into_iter()
+        .filter(|cable_id| rendered.contains(cable_id))
+        .collect()
+}
+
+pub(crate) fn select_cables_on(
+    ctx: &egui::Context,
+    canvas: Id,
+    cable_ids: impl IntoIterator<Item = CableId>,
+) {
+    let mut selected = vec![];
+    for cable_id in cable_ids {
+        if !selected.contains(&cable_id) {
+            selected.push(cable_id);
+        }
+    }
+    ctx.data_mut(|data| data.insert_temp(canvas.with("selected-cables"), Selection(selected)));
+}
+
+pub(crate) fn clear_selection_on(ctx: &egui::Context, canvas: Id) {
+    select_cables_on(ctx, canvas, []);
+    select_nodes_on(ctx, canvas, []);
+}
+
+pub(crate) fn is_cable_selected_on(ctx: &egui::Context, canvas: Id, cable_id: CableId) -> bool {
+    selection(ctx, canvas).contains(&cable_id)
+}
+
+fn selection(ctx: &egui::Context, canvas: Id) -> Vec<CableId> {
+    ctx.data(|data| {
+        data.get_temp::<Selection>(canvas.with("selected-cables"))
+            .unwrap_or_default()
+            .0
+    })
+}
+
+pub(crate) fn selected_nodes_on(ctx: &egui::Context, canvas: Id) -> Vec<NodeId> {
+    let shown: Vec<NodeId> = State::get_with_ctx(ctx)
+        .node_rects()
+        .into_iter()
+        .map(|node| node.node_id)
+        .collect();
+    node_selection(ctx, canvas)
+        .into_iter()
+        .filter(|node_id| shown.contains(node_id))
+        .collect()
+}
+
+pub(crate) fn select_nodes_on(
+    ctx: &egui::Context,
+    canvas: Id,
+    node_ids: impl IntoIterator<Item = NodeId>,
+) {
+    let mut selected = vec![];
+    for node_id in node_ids {
+        if !selected.contains(&node_id) {
+            selected.push(node_id);
+        }
+    }
+    ctx.data_mut(|data| data.insert_temp(canvas.with("selected-nodes"), NodeSelection(selected)));
+}
+
+pub(crate) fn is_node_selected_on(ctx: &egui::Context, canvas: Id, node_id: NodeId) -> bool {
+    node_selection(ctx, canvas).contains(&node_id)
+}
+
+fn node_selection(ctx: &egui::Context, canvas: Id) -> Vec<NodeId> {
+    ctx.data(|data| {
+        data.get_temp::<NodeSelection>(canvas.with("selected-nodes"))
+            .unwrap_or_default()
+            .0
+    })
+}
+
+pub(crate) fn add_to_selection(ctx: &egui::Context, canvas: Id, cable_id: CableId) {
+    let mut selected = selection(ctx, canvas);
+    selected.push(cable_id);
+    select_cables_on(ctx, canvas, selected);
+}
+
+pub(crate) fn remove_from_selection(ctx: &egui::Context, canvas: Id, cable_id: CableId) {
+    let selected = selection(ctx, canvas);
+    select_cables_on(
+        ctx,
+        canvas,
+        selected.into_iter().filter(|id| *id != cable_id),
+    );
+}
+
+/// Returns true if the modifiers add a clicked or boxed cable to the selection instead of replacing it.
+///
+/// Ctrl-click deletes a cable if deletion gestures are enabled, so only shift extends the selection then.
+pub(crate) fn extends_selection(modifiers: Modifiers, cable_deletion: bool) -> bool {
+    modifiers.shift || modifiers.command && !cable_deletion
+}
+
+/// Returns true if the response was clicked elsewhere in a way that deselects it:
+/// with the primary button, and not on a context menu like the one of another selected cable.
+pub(crate) fn deselected_by_click(response: &Response) -> bool {
+    let ctx = &response.ctx;
+    let on_menu = ctx
+        .input(|input| input.pointer.interact_pos())
+        .is_some_and(|pos| context_menu::is_on_menu(ctx, pos));
+    response.clicked_elsewhere()
+        && ctx.input(|input| input.pointer.button_clicked(PointerButton::Primary))
+        && !on_menu
+}
+
+/// Tracks the rubber-band box dragged on the background of a canvas.
+///
+/// This must be called before the contents of the canvas so that the contents get the pointer first.
+pub(crate) fn background(ui: &mut Ui, canvas: Id, rect: Rect) -> Response {
+    ui.interact(rect, canvas.with("background"), Sense::click_and_drag())
+}
+
+/// Paints the box while dragging and selects cables and nodes in the box when the drag ends.
+///
+/// This must be called with the settings of the canvas.
+pub(crate) fn box_select(ui: &Ui, canvas: Id, background: &Response, cable_deletion: bool) {
+    let ctx = ui.ctx();
+    if background.clicked()
+        && !extends_selection(ctx.input(|input| input.modifiers), cable_deletion)
+    {
+        clear_selection_on(ctx, canvas);
+    }
+    // The press origin is gone in the frame of the release.
+    let origin_id = canvas.with("box-origin");
+    // other buttons may pan the canvas
+    if background.drag_started_by(PointerButton::Primary) {
+        if let Some(origin) = ctx.input(|input| input.pointer.press_origin()) {
+            let origin = CanvasSettings::current(ctx).to_canvas(origin);
+            ctx.data_mut(|data| data.insert_temp(origin_id, origin));
+        }
+    }
+    let origin = ctx.data(|data| data.get_temp::<Pos2>(origin_id));
+    let (Some(origin), Some(pos)) = (origin, background.interact_pointer_pos()) else {
+        return;
+    };
+    let rect = Rect::from_two_pos(origin, pos);
+    if background.dragged() {
+        let visuals = &ui.visuals().selection;
+        ui.painter().rect(
+            rect,
+            0.0,
+            visuals.bg_fill.linear_multiply(0.3),
+            visuals.stroke,
+        );
+    }
+    if background.drag_stopped() {
+        ctx.data_mut(|data| data.remove::<Pos2>(origin_id));
+        // in the order the box reached them from the origin, and by ID on ties, to be deterministic
+        let drag = pos - origin;
+        let reached = |points: &[Pos2]| reached_at(points, rect, origin, drag);
+        let state = State::get_with_ctx(ctx);
+        let mut in_box: Vec<_> = state
+            .cable_paths()
+            .into_iter()
+            .filter(|path| path.canvas == Some(canvas) && path.shape.intersects_rect(rect))
+            .map(|path| (reached(&path.shape.polyline()), path.cable_id))
+            .collect();
+        in_box.sort_by(|(a, a_id), (b, b_id)| a.total_cmp(b).then(a_id.value().cmp(&b_id.value())));
+        let in_box = in_box.into_iter().map(|(_, cable_id)| cable_id);
+        let mut nodes_in_box: Vec<_> = state
+            .node_rects()
+            .into_iter()
+            .filter(|node| node.canvas == Some(canvas) && node.rect.intersects(rect))
+            .map(|node| {
+                let r = node.rect;
+                let corners = [
+                    r.left_top(),
+                    r.right_top(),
+                    r.left_bottom(),
+                    r.right_bottom(),
+                ];
+                (reached(&corners), node.node_id)
+            })
+            .collect();
+        nodes_in_box
+            .sort_by(|(a, a_id), (b, b_id)| a.total_cmp(b).then(a_id.value().cmp(&b_id.value())));
+        let nodes_in_box = nodes_in_box.into_iter().map(|(_, node_id)| node_id);
+        if extends_selection(ctx.input(|input| input.modifiers), cable_deletion) {
+            let mut selected = selection(ctx, canvas);
+            selected.extend(in_box);
+            select_cables_on(ctx, canvas, selected);
+            let mut selected = node_selection(ctx, canvas);
+            selected.extend(nodes_in_box);
+            select_nodes_on(ctx, canvas, selected);
+        } else {
+            select_cables_on(ctx, canvas, in_box);
+            select_nodes_on(ctx, canvas, nodes_in_box);
+        }
+    }
+}
+
+/// Returns how far along the drag the box first reaches a shape, given by points on it or its corners.
+fn reached_at(points: &[Pos2], rect: Rect, origin: Pos2, drag: Vec2) -> f32 {
+    points
+        .iter()
+        .map(|point| (rect.clamp(*point) - origin).dot(drag))
+        .fold(f32::INFINITY, f32::min)
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, Event};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    /// Renders two horizontal cables and returns the rects of their controls.
+    fn run(ctx: &egui::Context, modifiers: Modifiers, events: Vec<Event>) -> Vec<Rect> {
+        let canvas = CableCanvas::new("canvas").box_select(true);
+        run_canvas(ctx, canvas, modifiers, events, |ui| {
+            put_ports(
+                ui,
+                [
+                    (0, pos2(100.0, 100.0)),
+                    (1, pos2(300.0, 100.0)),
+                    (2, pos2(100.0, 300.0)),
+                    (3, pos2(300.0, 300.0)),
+                ],
+            );
+            vec![
+                ui.add(Cable::new(0, Plug::to(0), Plug::to(1))).rect,
+                ui.add(Cable::new(1, Plug::to(2), Plug::to(3))).rect,
+            ]
+        })
+        .inner
+    }
+
+    fn hover_and_click(ctx: &egui::Context, pos: Pos2, modifiers: Modifiers) {
+        // the cable control shows up on hover
+        for _ in 0..2 {
+            run(ctx, modifiers, vec![Event::PointerMoved(pos)]);
+        }
+        run(ctx, modifiers, click(pos, modifiers));
+    }
+
+    #[test]
+    fn shift_click_and_background_click() {
+        let ctx = egui::Context::default();
+        run(&ctx, Modifiers::NONE, vec![]);
+        let rects = run(&ctx, Modifiers::NONE, vec![]);
+        let (first, second) = (egui::Id::new(0), egui::Id::new(1));
+
+        hover_and_click(&ctx, rects[0].center(), Modifiers::NONE);
+        assert_eq!(selected_cables(&ctx, "canvas"), vec![first]);
+        hover_and_click(&ctx, rects[1].center(), Modifiers::SHIFT);
+        assert_eq!(selected_cables(&ctx, "canvas"), vec![first, second]);
+        // a plain click replaces the selection
+        hover_and_click(&ctx, rects[1].center(), Modifiers::NONE);
+        assert_eq!(selected_cables(&ctx, "canvas"), vec![second]);
+        hover_and_click(&ctx, pos2(500.0, 500.0), Modifiers::NONE);
+        assert_eq!(selected_cables(&ctx, "canvas"), vec![]);
+    }
+
+    /// Drags a box on the background.
+    fn drag_box(ctx: &egui::Context, from: Pos2, to: Pos2) {
+        run(ctx, Modifiers::NONE, vec![Event::PointerMoved(from)]);
+        run(ctx, Modifiers::NONE, vec![button(from, true)]);
+        for _ in 0..2 {
+            run(ctx, Modifiers::NONE, vec![Event::PointerMoved(to)]);
+        }
+        run(ctx, Modifiers::NONE, vec![button(to, false)]);
+    }
+
+    #[test]
+    fn box_select_cables() {
+        let ctx = egui::Context::default();
+        run(&ctx, Modifiers::NONE, vec![]);
+        run(&ctx, Modifiers::NONE, vec![]);
+        let (first, second) = (egui::Id::new(0), egui::Id::new(1));
+        // a selection on another canvas is left alone
+        select_cables(&ctx, "other", [second]);
+
+        // a box around the middle of both cables, in the order the box reaches them
+        drag_box(&ctx, pos2(150.0, 50.0), pos2(250.0, 400.0));
+        assert_eq!(selected_cables(&ctx, "canvas"), vec![first, second]);
+        drag_box(&ctx, pos2(250.0, 400.0), pos2(150.0, 50.0));
+        assert_eq!(selected_cables(&ctx, "canvas"), vec![second, first]);
+        assert_eq!(selected_cables(&ctx, "other"), vec![second]);
+    }
+}
@@ -2,11 +2,15 @@ use std::fmt::Debug;
 use std::hash::Hash;
 use std::{any::Any, collections::HashMap, sync::Arc};
 
-use egui::{Id, Pos2};
+use egui::{Id, LayerId};
 use egui::{Response, Vec2};
 
-use crate::cable::CableState;
-use crate::plug::{DraggedPlug, PlugState};
+use crate::cable::{CableEnds, CableState};
+use crate::node::NodeRect;
+use crate::path::CablePath;
+use crate::plug::{ArmedPlug, DraggedPlug, PlugState};
+use crate::port::{PortAnchor, PortClick};
+use crate::touch::{PointerId, TouchGrab};
 use crate::{cable::CableId, plug::PlugId, prelude::*};
 
 #[derive(Default, Clone, Debug)]
@@ -26,16 +30,33 @@ pub(crate) struct GenerationState {
 pub(crate) struct EphemeralState {
     pub plug_responses_of_cable: HashMap<Id, (Response, Response)>,
     pub event_of_plug: HashMap<Id, Event>,
+    pub event_of_cable: HashMap<Id, Event>,
+    pub pointer_of_plug: HashMap<Id, PointerId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 enum Key {
-    PortPos,
+    PortAnchor,
     PlugState,
     CableState,
     HoveredPort,
+    PortCandidate,
     DraggedPlug,
     CableControlSize,
+    ArmedPlug,
+    PortClick,
+    LastDisarmFrame,
+    FocusedPort,
+    PortLabel,
+    PortLayer,
+    NodeRect,
+    #[cfg(feature = "accesskit")]
+    PortWidgetId,
+    CableEnds,
+    TouchGrab,
+    CablePath,
+    DisconnectAllButton,
+    StackTop,
 }
 
 macro_rules! kvs {
@@ -95,6 +116,20 @@ impl State {
             .map(|data| data.downcast_ref::<V>().unwrap().clone())
     }
 
+    /// Returns all values of the key in the current and the previous generations.
+    fn values<V: Clone + Send + Sync + 'static>(&self, key: Key) -> Vec<V> {
+        let mut values = HashMap::new();
+        for generation in [&self.previous, &self.current] {
+            if let Some(kv) = generation.kvs.get(&key) {
+                values.extend(kv.iter().map(|(id, data)| (*id, data.clone())));
+            }
+        }
+        values
+            .into_values()
+            .map(|data| data.downcast_ref::<V>().unwrap().clone())
+            .collect()
+    }
+
     fn update_data<V: Send + Sync + 'static>(&mut self, key: Key, data: V) {
         self.current.kv.insert(key, Arc::new(data));
     }
@@ -110,7 +145,7 @@ impl State {
         if self
             .current
             .kvs
-            .get(&Key::PortPos)
+            .get(&Key::PortAnchor)
             .and_then(|kv| kv.get(&Id::new(port_id)))
             .is_some()
         {
@@ -118,7 +153,13 @@ impl State {
         }
     }
 
-    kvs!(PortPos, port_pos, update_port_pos, PortId, Pos2);
+    kvs!(
+        PortAnchor,
+        port_anchor,
+        update_port_anchor,
+        PortId,
+        PortAnchor
+    );
     kvs!(PlugState, plug_state, update_plug_state, PlugId, PlugState);
     kvs!(
         CableState,
@@ -135,8 +176,157 @@ impl State {
         Vec2
     );
 
-    kv!(HoveredPort, hovered_port_id, update_hovered_port_id, PortId);
-    kv!(DraggedPlug, dragged_plug, update_dragged_plug, DraggedPlug);
+    kvs!(PortLabel, port_label, update_port_label, PortId, String);
+    kvs!(PortLayer, port_layer, update_port_layer, PortId, LayerId);
+
+    pub(crate) fn update_node_rect(&mut self, node_id: Id, rect: NodeRect) {
+        self.update_kv(Key::NodeRect, node_id, rect);
+    }
+
+    pub(crate) fn node_rect(&self, node_id: &Id) -> Option<NodeRect> {
+        self.get_kv(Key::NodeRect, node_id)
+    }
+
+    /// Returns the rects of all nodes rendered recently.
+    pub(crate) fn node_rects(&self) -> Vec<NodeRect> {
+        self.values(Key::NodeRect)
+    }
+    #[cfg(feature = "accesskit")]
+    kvs!(
+        PortWidgetId,
+        port_widget_id,
+        update_port_widget_id,
+        PortId,
+        Id
+    );
+    pub(crate) fn update_cable_ends(&mut self, cable_id: CableId, ends: CableEnds) {
+        self.update_kv(Key::CableEnds, cable_id, ends);
+    }
+
+    /// Returns the ends of all cables rendered recently.
+    pub(crate) fn all_cable_ends(&self) -> Vec<CableEnds> {
+        self.values(Key::CableEnds)
+    }
+
+    /// Returns the anchors of all ports rendered recently.
+    pub(crate) fn port_anchors(&self) -> Vec<PortAnchor> {
+        self.values(Key::PortAnchor)
+    }
+
+    pub(crate) fn update_cable_path(&mut self, path: CablePath) {
+        self.update_kv(Key::CablePath, path.cable_id, path);
+    }
+
+    /// Returns the paths of all cables rendered recently.
+    pub(crate) fn cable_paths(&self) -> Vec<CablePath> {
+        self.values(Key::CablePath)
+    }
+
+    kvs!(
+        HoveredPort,
+        hovered_port_id,
+        update_hovered_port_id,
+        PointerId,
+        PortId
+    );
+
+    // the distance of the hovered port from the plug of the pointer, and the frame it was found in
+    kvs!(
+        PortCandidate,
+        port_candidate,
+        update_port_candidate,
+        PointerId,
+        (f32, u64)
+    );
+
+    /// Makes the port hovered by the plug of the pointer, unless a nearer port did in the same frame.
+    pub(crate) fn offer_hovered_port(
+        &mut self,
+        pointer: PointerId,
+        port_id: PortId,
+        distance: f32,
+        frame: u64,
+    ) {
+        let nearest = self
+            .port_candidate(&pointer)
+            .map_or(true, |(nearest, found)| {
+                found != frame || distance < nearest
+            });
+        if nearest {
+            self.update_port_candidate(pointer, (distance, frame));
+            self.update_hovered_port_id(pointer, port_id);
+        }
+    }
+
+    pub(crate) fn update_dragged_plug(&mut self, pointer: PointerId, plug: DraggedPlug) {
+        self.update_kv(Key::DraggedPlug, pointer, plug);
+    }
+
+    /// `None` releases the touch.
+    pub(crate) fn update_touch_grab(&mut self, pointer: PointerId, grab: Option<TouchGrab>) {
+        self.update_kv(Key::TouchGrab, pointer, grab);
+    }
+
+    /// Returns true if the port is hovered by any pointer.
+    pub(crate) fn is_port_hovered(&self, port_id: &PortId) -> bool {
+        self.values::<PortId>(Key::HoveredPort).contains(port_id)
+    }
+
+    /// Returns plugs dragged by any pointer.
+    pub(crate) fn dragged_plugs(&self) -> Vec<DraggedPlug> {
+        self.values(Key::DraggedPlug)
+    }
+
+    /// Returns touches holding a plug.
+    pub(crate) fn touch_grabs(&self) -> Vec<TouchGrab> {
+        self.values::<Option<TouchGrab>>(Key::TouchGrab)
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+    kv!(
+        ArmedPlug,
+        armed_plug_state,
+        update_armed_plug,
+        Option<ArmedPlug>
+    );
+    kv!(PortClick, port_click, update_port_click, PortClick);
+    kvs!(StackTop, stack_top, update_stack_top, PortId, usize);
+    kvs!(
+        DisconnectAllButton,
+        disconnect_all_button,
+        update_disconnect_all_button,
+        PortId,
+        egui::Id
+    );
+    kv!(
+        LastDisarmFrame,
+        last_disarm_frame,
+        update_last_disarm_frame,
+        u64
+    );
+    kv!(
+        FocusedPort,
+        focused_port_state,
+        update_focused_port_state,
+        (PortId, u64)
+    );
+
+    pub(crate) fn update_focused_port(&mut self, port_id: PortId, frame: u64) {
+        self.update_focused_port_state((port_id, frame));
+    }
+
+    /// Returns the port which had keyboard focus in the given or the previous frame.
+    pub(crate) fn focused_port(&self, frame: u64) -> Option<PortId> {
+        self.focused_port_state()
+            .filter(|(_, focused_frame)| focused_frame + 1 >= frame)
+            .map(|(port_id, _)| port_id)
+    }
+
+    /// Returns the plug armed by a click-click connection gesture.
+    pub(crate) fn armed_plug(&self) -> Option<ArmedPlug> {
+        self.armed_plug_state().flatten()
+    }
 
     pub fn get_cloned(ui: &mut egui::Ui) -> Self {
         ui.data_mut(|data| {
@@ -155,62 +345,98 @@ impl State {
         })
     }
 
-    pub fn get_with_ctx(ctx: &mut egui::Context) -> Arc<Self> {
+    pub fn get_with_ctx(ctx: &egui::Context) -> Arc<Self> {
         ctx.data_mut(|data| {
             data.get_persisted::<Arc<State>>(Id::NULL)
                 .unwrap_or_default()
         })
     }
 
+    pub fn update_with_ctx(ctx: &egui::Context, f: impl FnOnce(&mut State)) {
+        ctx.data_mut(|data| {
+            let state = data.get_persisted_mut_or_default::<Arc<State>>(Id::NULL);
+            f(Arc::make_mut(state));
+        });
+    }
+
     pub fn store_to(self, ui: &mut egui::Ui) {
         ui.data_mut(|data| {
             data.insert_persisted(Id::NULL, Arc::new(self));
         });
     }
+
+    pub fn store_to_ctx(self, ctx: &egui::Context) {
+        ctx.data_mut(|data| {
+            data.insert_persisted(Id::NULL, Arc::new(self));
+        });
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use egui::Pos2;
+
     use super::*;
 
+    /// Returns where a point-sized plug rests on the port.
+    fn plug_pos(state: &State, port_id: u32) -> Option<Pos2> {
+        state
+            .port_anchor(&PortId::new(port_id))
+            .map(|anchor| anchor.plug_pos(Vec2::ZERO))
+    }
+
     #[test]
     fn port_pos() {
+        let at_zero = || PortAnchor {
+            rect: egui::Rect::from_min_size(Pos2::ZERO, Vec2::ZERO),
+            view: egui::Rect::EVERYTHING,
+            plug: None,
+            line: None,
+            frame: 0,
+            canvas: None,
+        };
         let mut state = State::default();
         // first gen
         state.advance_generation_if_twice(PortId::new(1));
-        state.update_port_pos(PortId::new(1), Pos2::ZERO);
+        state.update_port_anchor(PortId::new(1), at_zero());
         state.advance_generation_if_twice(PortId::new(2));
-        state.update_port_pos(PortId::new(2), Pos2::ZERO);
+        state.update_port_anchor(PortId::new(2), at_zero());
         state.advance_generation_if_twice(PortId::new(3));
-        state.update_port_pos(PortId::new(3), Pos2::ZERO);
+        state.update_port_anchor(PortId::new(3), at_zero());
         // second gen
         state.advance_generation_if_twice(PortId::new(1));
-        state.update_port_pos(PortId::new(1), Pos2::ZERO);
+        state.update_port_anchor(PortId::new(1), at_zero());
         state.advance_generation_if_twice(PortId::new(2));
-        state.update_port_pos(PortId::new(2), Pos2::ZERO);
+        state.update_port_anchor(PortId::new(2), at_zero());
 
         // assert not advanced for third gen
-        assert_eq!(state.port_pos(&PortId::new(3)), Some(Pos2::ZERO));
+        assert_eq!(plug_pos(&state, 3), Some(Pos2::ZERO));
 
         // third gen
         state.advance_generation_if_twice(PortId::new(1));
-        state.update_port_pos(PortId::new(1), Pos2::ZERO);
+        state.update_port_anchor(PortId::new(1), at_zero());
 
-        assert_eq!(state.port_pos(&PortId::new(1)), Some(Pos2::ZERO));
-        assert_eq!(state.port_pos(&PortId::new(2)), Some(Pos2::ZERO));
-        assert_eq!(state.port_pos(&PortId::new(3)), None);
+        assert_eq!(plug_pos(&state, 1), Some(Pos2::ZERO));
+        assert_eq!(plug_pos(&state, 2), Some(Pos2::ZERO));
+        assert_eq!(plug_pos(&state, 3), None);
     }
 
     #[test]
     fn update_hovered_port_id() {
         let mut state = State::default();
-        state.update_hovered_port_id(PortId::new(1));
-        assert_eq!(state.hovered_port_id(), Some(PortId::new(1)));
+        state.update_hovered_port_id(PointerId::Primary, PortId::new(1));
+        assert_eq!(
+            state.hovered_port_id(&PointerId::Primary),
+            Some(PortId::new(1))
+        );
 
         state.next_generation();
-        assert_eq!(state.hovered_port_id(), Some(PortId::new(1)));
+        assert_eq!(
+            state.hovered_port_id(&PointerId::Primary),
+            Some(PortId::new(1))
+        );
 
         state.next_generation();
-        assert_eq!(state.hovered_port_id(), None);
+        assert_eq!(state.hovered_port_id(&PointerId::Primary), None);
     }
 }
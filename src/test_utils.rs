@@ -0,0 +1,88 @@
+//! Helpers for the tests running frames with ports and cables.
+
+use egui::{pos2, vec2, Event, Key, Modifiers, PointerButton, Pos2, Rect, Ui};
+
+use crate::canvas::{CableCanvas, CanvasResponse};
+use crate::port::Port;
+
+/// Runs a frame with the events while holding the modifiers, and shows the contents in a central panel.
+pub(crate) fn run_frame<R>(
+    ctx: &egui::Context,
+    modifiers: Modifiers,
+    events: Vec<Event>,
+    add_contents: impl FnOnce(&egui::Context, &mut Ui) -> R,
+) -> R {
+    let input = egui::RawInput {
+        modifiers,
+        events,
+        ..Default::default()
+    };
+    let mut output = None;
+    let _ = ctx.run(input, |ctx| {
+        egui::CentralPanel::default().show(ctx, |ui| {
+            output = Some(add_contents(ctx, ui));
+        });
+    });
+    output.unwrap()
+}
+
+/// Runs a frame like [`run_frame`] with the canvas filling the central panel.
+pub(crate) fn run_canvas<R>(
+    ctx: &egui::Context,
+    canvas: CableCanvas,
+    modifiers: Modifiers,
+    events: Vec<Event>,
+    add_contents: impl FnOnce(&mut Ui) -> R,
+) -> CanvasResponse<R> {
+    run_frame(ctx, modifiers, events, |_, ui| {
+        canvas.show(ui, add_contents)
+    })
+}
+
+/// Puts 16×16 ports with their top-left corners at the positions.
+pub(crate) fn put_ports(ui: &mut Ui, ports: impl IntoIterator<Item = (i32, Pos2)>) {
+    for (port, pos) in ports {
+        ui.put(Rect::from_min_size(pos, vec2(16.0, 16.0)), Port::new(port));
+    }
+}
+
+/// Puts the ports 0 and 1 side by side, at (100, 100) and (300, 100).
+pub(crate) fn two_ports(ui: &mut Ui) {
+    put_ports(ui, [(0, pos2(100.0, 100.0)), (1, pos2(300.0, 100.0))]);
+}
+
+pub(crate) fn pointer_button(
+    pos: Pos2,
+    button: PointerButton,
+    pressed: bool,
+    modifiers: Modifiers,
+) -> Event {
+    Event::PointerButton {
+        pos,
+        button,
+        pressed,
+        modifiers,
+    }
+}
+
+/// Presses or releases the primary button without modifiers.
+pub(crate) fn button(pos: Pos2, pressed: bool) -> Event {
+    pointer_button(pos, PointerButton::Primary, pressed, Modifiers::NONE)
+}
+
+/// Presses and releases the primary button in the same frame.
+pub(crate) fn click(pos: Pos2, modifiers: Modifiers) -> Vec<Event> {
+    [true, false]
+        .map(|pressed| pointer_button(pos, PointerButton::Primary, pressed, modifiers))
+        .into()
+}
+
+pub(crate) fn key(key: Key, modifiers: Modifiers) -> Event {
+    Event::Key {
+        key,
+        physical_key: None,
+        pressed: true,
+        repeat: false,
+        modifiers,
+    }
+}
@@ -0,0 +1,224 @@
+use egui::{Id, Pos2, TouchPhase, Vec2};
+
+use crate::{canvas::CanvasSettings, plug::PlugId};
+
+/// The pointer which moves a plug.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerId {
+    /// The mouse, or the first touch which egui integrations translate into mouse events.
+    Primary,
+    /// Any other touch.
+    Touch { device_id: u64, touch_id: u64 },
+}
+
+/// A touch holding a plug.
+#[derive(Debug, Clone)]
+pub(crate) struct TouchGrab {
+    pub pointer: PointerId,
+    pub plug_id: PlugId,
+    // offset from the top-left of the plug to the touch
+    pub offset: Vec2,
+    pub pos: Pos2,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct TouchEvent {
+    pub pointer: PointerId,
+    pub phase: TouchPhase,
+    pub pos: Pos2,
+}
+
+/// The touch which egui integrations translate into mouse events, as device and touch ids.
+#[derive(Debug, Clone, Copy, Default)]
+struct MouseTouch {
+    frame: u64,
+    // the touch when the frame starts and when it ends
+    before: Option<(u64, u64)>,
+    after: Option<(u64, u64)>,
+}
+
+/// Returns touch events of this frame except the ones of the touch translated into mouse events.
+/// Positions are in the coordinates of the contents of the canvas.
+pub(crate) fn touch_events(ctx: &egui::Context) -> Vec<TouchEvent> {
+    let settings = CanvasSettings::current(ctx);
+    let frame = ctx.frame_nr();
+    let mut mouse_touch = ctx.data_mut(|data| {
+        let mouse_touch = data.get_temp_mut_or_default::<MouseTouch>(Id::NULL);
+        if mouse_touch.frame != frame {
+            *mouse_touch = MouseTouch {
+                frame,
+                before: mouse_touch.after,
+                after: mouse_touch.after,
+            };
+        }
+        *mouse_touch
+    });
+    let events = ctx.input(|input| {
+        // egui integrations translate a touch into mouse events when it starts while no other
+        // touch is translated, and send the pointer press in the same frame as the touch start.
+        let pressed = input
+            .events
+            .iter()
+            .any(|event| matches!(event, egui::Event::PointerButton { pressed: true, .. }));
+        let mut current = mouse_touch.before;
+        let events = input
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                egui::Event::Touch {
+                    device_id,
+                    id,
+                    phase,
+                    pos,
+                    ..
+                } => {
+                    let touch = (device_id.0, id.0);
+                    if *phase == TouchPhase::Start && pressed && current.is_none() {
+                        current = Some(touch);
+                    }
+                    if current != Some(touch) {
+                        return Some(TouchEvent {
+                            pointer: PointerId::Touch {
+                                device_id: device_id.0,
+                                touch_id: id.0,
+                            },
+                            phase: *phase,
+                            pos: settings.to_canvas(*pos),
+                        });
+                    }
+                    if matches!(phase, TouchPhase::End | TouchPhase::Cancel) {
+                        current = None;
+                    }
+                    None
+                }
+                _ => None,
+            })
+            .collect();
+        mouse_touch.after = current;
+        events
+    });
+    ctx.data_mut(|data| data.insert_temp(Id::NULL, mouse_touch));
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, TouchDeviceId, TouchId};
+
+    use super::*;
+    use crate::{prelude::*, test_utils::*};
+
+    fn touch(id: u64, phase: TouchPhase, pos: Pos2) -> egui::Event {
+        egui::Event::Touch {
+            device_id: TouchDeviceId(1),
+            id: TouchId(id),
+            phase,
+            pos,
+            force: None,
+        }
+    }
+
+    /// Renders two ports and two cables with a free out-plug,
+    /// and returns the connection and the pointer of each out-plug.
+    fn run(
+        ctx: &egui::Context,
+        events: Vec<egui::Event>,
+    ) -> Vec<(Option<PortId>, Option<PointerId>)> {
+        run_frame(ctx, Default::default(), events, |_, ui| {
+            put_ports(ui, [(0, pos2(300.0, 100.0)), (1, pos2(300.0, 300.0))]);
+            let mut output = vec![];
+            for (cable, y) in [(0, 100.0), (1, 300.0)] {
+                let out_plug = Plug::unplugged().pos(pos2(100.0, y));
+                let mut response = ui.add(Cable::new(cable, Plug::unplugged(), out_plug));
+                let mut out_plug = response.out_plug();
+                output.push((out_plug.connected_to(), out_plug.pointer()));
+            }
+            output
+        })
+    }
+
+    #[test]
+    fn two_touches_drag_two_plugs() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![]);
+        run(&ctx, vec![]);
+        run(
+            &ctx,
+            vec![
+                touch(1, TouchPhase::Start, pos2(108.0, 108.0)),
+                touch(2, TouchPhase::Start, pos2(108.0, 308.0)),
+            ],
+        );
+        // cross the touches
+        let moves = vec![
+            touch(1, TouchPhase::Move, pos2(308.0, 308.0)),
+            touch(2, TouchPhase::Move, pos2(308.0, 108.0)),
+        ];
+        run(&ctx, moves.clone());
+        run(&ctx, moves);
+        let output = run(
+            &ctx,
+            vec![
+                touch(1, TouchPhase::End, pos2(308.0, 308.0)),
+                touch(2, TouchPhase::End, pos2(308.0, 108.0)),
+            ],
+        );
+        let touch_pointer = |touch_id| PointerId::Touch {
+            device_id: 1,
+            touch_id,
+        };
+        assert_eq!(
+            output,
+            vec![
+                (Some(PortId::new(1)), Some(touch_pointer(1))),
+                (Some(PortId::new(0)), Some(touch_pointer(2))),
+            ]
+        );
+    }
+
+    #[test]
+    fn touch_translated_into_mouse_events_is_the_mouse() {
+        let ctx = egui::Context::default();
+        run(&ctx, vec![]);
+        run(&ctx, vec![]);
+        // the integration translates the first touch into a mouse press at another point
+        // than the touch, like when it rounds positions to pixels
+        run(
+            &ctx,
+            vec![
+                touch(1, TouchPhase::Start, pos2(108.0, 108.0)),
+                egui::Event::PointerMoved(pos2(110.0, 110.0)),
+                button(pos2(110.0, 110.0), true),
+                touch(2, TouchPhase::Start, pos2(108.0, 308.0)),
+            ],
+        );
+        let moves = vec![
+            touch(1, TouchPhase::Move, pos2(308.0, 308.0)),
+            egui::Event::PointerMoved(pos2(310.0, 310.0)),
+            touch(2, TouchPhase::Move, pos2(308.0, 108.0)),
+        ];
+        run(&ctx, moves.clone());
+        run(&ctx, moves);
+        let output = run(
+            &ctx,
+            vec![
+                touch(1, TouchPhase::End, pos2(308.0, 308.0)),
+                button(pos2(310.0, 310.0), false),
+                touch(2, TouchPhase::End, pos2(308.0, 108.0)),
+            ],
+        );
+        assert_eq!(
+            output,
+            vec![
+                (Some(PortId::new(1)), Some(PointerId::Primary)),
+                (
+                    Some(PortId::new(0)),
+                    Some(PointerId::Touch {
+                        device_id: 1,
+                        touch_id: 2,
+                    }),
+                ),
+            ]
+        );
+    }
+}
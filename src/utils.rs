@@ -1,4 +1,6 @@
-use egui::{style::WidgetVisuals, vec2, Response, Ui, Vec2};
+use egui::{style::WidgetVisuals, vec2, Id, LayerId, Order, Pos2, Rect, Response, Ui, Vec2};
+
+use crate::canvas::CanvasSettings;
 
 pub fn widget_visuals(ui: &mut Ui, response: &Response) -> WidgetVisuals {
     if response.hovered() {
@@ -10,5 +12,52 @@ pub fn widget_visuals(ui: &mut Ui, response: &Response) -> WidgetVisuals {
     ui.visuals().widgets.inactive
 }
 
+/// Shows the contents at the position on a foreground area, or directly on the layer if given.
+pub(crate) fn show_on_layer<R>(
+    ctx: &egui::Context,
+    id: Id,
+    layer: Option<LayerId>,
+    pos: Pos2,
+    area: impl FnOnce(egui::Area) -> egui::Area,
+    add_contents: impl FnOnce(&mut Ui) -> R,
+) -> R {
+    let Some(layer) = layer else {
+        let shown = area(
+            egui::Area::new(id)
+                .current_pos(pos)
+                .order(Order::Foreground),
+        )
+        .show(ctx, add_contents);
+        // follow the pan and zoom of the canvas
+        let transform = CanvasSettings::current(ctx).transform.unwrap_or_default();
+        ctx.set_transform_layer(shown.response.layer_id, transform);
+        return shown.inner;
+    };
+    let screen_rect = ctx.screen_rect();
+    let max_rect = Rect::from_min_max(pos, screen_rect.max.max(pos + Vec2::splat(32.0)));
+    let mut ui = Ui::new(ctx.clone(), layer, id, max_rect, screen_rect);
+    add_contents(&mut ui)
+}
+
+/// Returns the layer painted later of the two.
+///
+/// Layers of different orders are compared by the order. Windows are compared by which one is in front where they overlap,
+/// and either one is fine if they don't overlap.
+pub(crate) fn upper_layer(ctx: &egui::Context, a: LayerId, b: LayerId) -> LayerId {
+    if a.order != b.order {
+        return if a.order < b.order { b } else { a };
+    }
+    let overlap = ctx.memory(|memory| {
+        memory
+            .area_rect(a.id)
+            .zip(memory.area_rect(b.id))
+            .map(|(a, b)| a.intersect(b))
+    });
+    match overlap {
+        Some(overlap) if overlap.is_positive() && ctx.layer_id_at(overlap.center()) == Some(b) => b,
+        _ => a,
+    }
+}
+
 // should be even number because SIZE / 2.0 produces .5 and it may break rendering.
 pub const SIZE: Vec2 = vec2(16.0, 16.0);